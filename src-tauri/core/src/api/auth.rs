@@ -0,0 +1,795 @@
+use crate::{
+    api::{ApiContext, AuthType, ClientworksError, parse_id, vault::{Envelope, Vault}},
+    client::{
+        AuthProtocol,
+        ClientController,
+        auth::{
+            MinecraftProfile,
+            self, AuthState, Endpoints
+        },
+        hooks::{Payload, Publisher},
+        yggdrasil::{self, YggdrasilServer, YggdrasilToken}
+    },
+    AppState
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs, path::Path,
+    sync::Mutex,
+    time::{
+        SystemTime,
+        UNIX_EPOCH
+    },
+    sync::Arc,
+    ops::DerefMut,
+    time::Duration
+};
+use azalea::{
+    Account,
+    ecs::error::info
+};
+use azalea_auth::{AccessTokenResponse, cache::ExpiringValue};
+use log::{debug, error, info, warn};
+use uuid::Uuid;
+
+/// What a cached account refreshes its Minecraft session token with.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(untagged)]
+pub enum Credentials {
+    Microsoft {
+        msa: ExpiringValue<AccessTokenResponse>
+    },
+    /// The access token is the Minecraft session token, see [`yggdrasil::YggdrasilToken`]
+    Yggdrasil {
+        server: YggdrasilServer,
+        client_token: String
+    },
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct MinecraftAuthCache {
+    pub access_token: String,
+    pub expiration: u64,
+    #[serde(flatten)]
+    pub credentials: Credentials,
+    pub profile: MinecraftProfile
+}
+
+/// How long before their expiration the cached tokens are refreshed, see [`refresh_tokens`].
+pub const REFRESH_MARGIN: Duration = Duration::from_secs(30 * 60);
+
+/// How often the authentication cache is scanned for expiring tokens, see [`refresh_tokens`].
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+impl MinecraftAuthCache {
+    pub fn has_expired(&self) -> bool {
+        self.expires_within(Duration::ZERO)
+    }
+
+    /// Whether the Minecraft session token expires in less than `margin`.
+    pub fn expires_within(&self, margin: Duration) -> bool {
+        self.expiration
+            < SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() + margin.as_secs()
+    }
+
+    pub fn auth_type(&self) -> AuthType {
+        match self.credentials {
+            Credentials::Microsoft { .. } => AuthType::Microsoft,
+            Credentials::Yggdrasil { .. } => AuthType::Yggdrasil,
+        }
+    }
+
+    /// The credentials controllers of the account authenticate with.
+    pub fn protocol(&self) -> AuthProtocol {
+        match &self.credentials {
+            Credentials::Microsoft { msa } => AuthProtocol::Microsoft(
+                self.access_token.clone(),
+                Box::new(msa.clone()),
+                Box::new(self.profile.clone()),
+            ),
+            Credentials::Yggdrasil { server, .. } => AuthProtocol::Yggdrasil(
+                self.access_token.clone(),
+                Box::new(server.clone()),
+                Box::new(self.profile.clone()),
+            ),
+        }
+    }
+
+    /// Caches a Yggdrasil session, considered to expire after [`yggdrasil::TOKEN_LIFETIME`].
+    fn yggdrasil(server: YggdrasilServer, token: YggdrasilToken, profile: MinecraftProfile) -> Self {
+        Self {
+            access_token: token.access_token,
+            expiration: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs() + yggdrasil::TOKEN_LIFETIME.as_secs(),
+            credentials: Credentials::Yggdrasil { server, client_token: token.client_token },
+            profile
+        }
+    }
+
+    /// Refreshes the Minecraft session token: along with the MSA token for Microsoft accounts,
+    /// see [`auth::refresh_session`], or with the client token for Yggdrasil accounts.
+    ///
+    /// # Parameters
+    /// * `client` - what tokens are refreshed with, see [`ApiContext::http_client`]
+    /// * `endpoints` - the services Microsoft tokens are refreshed with, see
+    ///   [`ApiContext::ms_endpoints`]
+    async fn refreshed<Scb>(
+        &self,
+        client: &reqwest::Client,
+        endpoints: &Endpoints,
+        mut state_callback: Scb
+    ) -> Result<Self, String>
+    where
+        Scb: FnMut(&AuthState),
+    {
+        match &self.credentials {
+            Credentials::Microsoft { msa } => {
+                let (msa, token) = auth::refresh_session(state_callback, client, endpoints, msa).await?;
+                Ok(Self {
+                    access_token: token.access_token,
+                    expiration: token.expires_at,
+                    credentials: Credentials::Microsoft { msa },
+                    profile: self.profile.clone()
+                })
+            }
+            Credentials::Yggdrasil { server, client_token } => {
+                let token = YggdrasilToken {
+                    access_token: self.access_token.clone(),
+                    client_token: client_token.clone()
+                };
+                let token = yggdrasil::refresh(client, server, &token).await
+                    .inspect_err(|e| state_callback(&AuthState::Error(format!(
+                        "Failed to refresh Yggdrasil token. Re-authentication is required. ({e})"
+                    ))))?;
+                state_callback(&AuthState::Working("Successfully refreshed Yggdrasil token".to_owned()));
+                Ok(Self::yggdrasil(server.clone(), token, self.profile.clone()))
+            }
+        }
+    }
+}
+
+/// How the authentication cache is stored.
+#[derive(Default)]
+enum Protection {
+    /// As plaintext JSON
+    #[default]
+    Plain,
+    /// Encrypted, and not unlocked yet: no account is loaded, and nothing is written.
+    Locked(Envelope),
+    /// Encrypted, and unlocked with its passphrase.
+    Unlocked(Vault),
+    /// The file couldn't be parsed, e.g. after a truncated write or with an envelope this
+    /// version doesn't know: no account is loaded, and the file is never overwritten.
+    Unreadable(String),
+}
+
+/// Whether the authentication cache is encrypted, and if so, whether it has been unlocked.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum AuthCacheState {
+    Plain,
+    Locked,
+    Unlocked,
+    /// The file couldn't be parsed, and is left untouched
+    Unreadable,
+}
+
+/// The content of `auth_cache.json`, either the accounts as is or encrypted.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CacheFile {
+    Encrypted(Envelope),
+    Plain(HashMap<String, MinecraftAuthCache>),
+}
+
+/// The cached Microsoft and Yggdrasil accounts by login key, optionally encrypted at rest with a passphrase,
+/// see [`set_auth_passphrase`].
+#[derive(Default)]
+pub struct AuthCache(HashMap<String, MinecraftAuthCache>, Protection);
+
+impl AuthCache {
+    pub fn from_file(path: &Path) -> Self {
+        let path = path.join("auth_cache.json");
+        if !path.exists() {
+            fs::write(&path, "{}");
+        }
+        let file = match fs::read_to_string(&path) {
+            Ok(file) => file,
+            Err(e) => {
+                error!("Failed to read authentication cache: {e}");
+                return Self(HashMap::new(), Protection::Unreadable(e.to_string()))
+            }
+        };
+        match serde_json::from_str(&file) {
+            Ok(CacheFile::Encrypted(envelope)) => {
+                info!("Authentication cache is encrypted, waiting to be unlocked");
+                Self(HashMap::new(), Protection::Locked(envelope))
+            }
+            Ok(CacheFile::Plain(accounts)) => {
+                info!("Cached accounts: {} [{:?}]", accounts.len(), accounts.keys());
+                Self(accounts, Protection::Plain)
+            }
+            Err(e) => {
+                error!("Failed to parse authentication cache, leaving it untouched: {e}");
+                Self(HashMap::new(), Protection::Unreadable(e.to_string()))
+            }
+        }
+    }
+
+    /// Writes the cache, encrypting it if it has a passphrase. A locked cache is left untouched.
+    ///
+    /// The cache is written to a temporary file first, then moved over the previous one, so
+    /// that an interrupted write never leaves a truncated cache behind.
+    ///
+    /// # Errors
+    /// * [`ClientworksError::Io`] - if the file couldn't be parsed when loaded, or can't be written
+    pub fn write_to_file(&self, path: &Path) -> Result<(), ClientworksError> {
+        let json = match &self.1 {
+            Protection::Plain => serde_json::to_string_pretty(&self.0).unwrap(),
+            Protection::Locked(_) => {
+                warn!("Authentication cache is locked, not writing it");
+                return Ok(())
+            }
+            Protection::Unlocked(vault) => {
+                let envelope = vault.seal(&serde_json::to_vec(&self.0).unwrap())?;
+                serde_json::to_string_pretty(&envelope).unwrap()
+            }
+            Protection::Unreadable(reason) => return Err(ClientworksError::Io {
+                reason: format!("The authentication cache couldn't be read, not overwriting it ({reason})")
+            })
+        };
+        let temp = path.join("auth_cache.json.tmp");
+        fs::write(&temp, json)?;
+        fs::rename(temp, path.join("auth_cache.json"))?;
+        Ok(())
+    }
+
+    pub fn state(&self) -> AuthCacheState {
+        match self.1 {
+            Protection::Plain => AuthCacheState::Plain,
+            Protection::Locked(_) => AuthCacheState::Locked,
+            Protection::Unlocked(_) => AuthCacheState::Unlocked,
+            Protection::Unreadable(_) => AuthCacheState::Unreadable,
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.state() == AuthCacheState::Locked
+    }
+
+    /// Decrypts the cached accounts. Does nothing if the cache isn't locked.
+    ///
+    /// # Errors
+    /// * [`ClientworksError::InvalidPassphrase`] - if the passphrase is wrong
+    /// * [`ClientworksError::Io`] - if the encrypted cache is corrupted
+    pub fn unlock(&mut self, passphrase: &str) -> Result<(), ClientworksError> {
+        let Protection::Locked(envelope) = &self.1 else {
+            return Ok(())
+        };
+        let (vault, data) = Vault::open(passphrase, envelope)?;
+        self.0 = serde_json::from_slice(&data)
+            .map_err(|e| ClientworksError::Io { reason: format!("Invalid authentication cache: {e}") })?;
+        self.1 = Protection::Unlocked(vault);
+        info!("Unlocked authentication cache, cached accounts: {} [{:?}]", self.0.len(), self.0.keys());
+        Ok(())
+    }
+
+    /// Encrypts the cache with the `new` passphrase, or stores it as plaintext if `None`. The
+    /// `current` passphrase is required if the cache is already encrypted.
+    ///
+    /// # Errors
+    /// * [`ClientworksError::AuthCacheLocked`] - if the cache hasn't been unlocked
+    /// * [`ClientworksError::InvalidPassphrase`] - if `current` is wrong, or `new` is empty
+    pub fn set_passphrase(&mut self, current: Option<&str>, new: Option<&str>) -> Result<(), ClientworksError> {
+        match (&self.1, current) {
+            (Protection::Locked(_), _) => return Err(ClientworksError::AuthCacheLocked),
+            (Protection::Unlocked(vault), Some(current)) if vault.verify(current) => {}
+            (Protection::Unlocked(_), _) => {
+                return Err(ClientworksError::InvalidPassphrase { reason: "Wrong current passphrase".to_string() })
+            }
+            (Protection::Plain, _) => {}
+            (Protection::Unreadable(reason), _) => {
+                return Err(ClientworksError::Io { reason: format!("The authentication cache couldn't be read ({reason})") })
+            }
+        }
+        self.1 = match new {
+            Some(new) => Protection::Unlocked(Vault::new(new)?),
+            None => Protection::Plain
+        };
+        Ok(())
+    }
+
+    pub fn get_from_mc_uuid(&self, uuid: &Uuid) -> Option<&MinecraftAuthCache> {
+        for (key, cache) in self.0.iter() {
+            if cache.profile.uuid.eq(uuid) {
+                return Some(cache)
+            }
+        }
+        None
+    }
+
+    pub fn get_key_from_mc_uuid(&self, uuid: &Uuid) -> Option<&String> {
+        for (key, cache) in self.0.iter() {
+            if cache.profile.uuid.eq(uuid) {
+                return Some(key)
+            }
+        }
+        None
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AuthProgress {
+    state: String,
+    message: String,
+}
+
+impl From<&AuthState> for AuthProgress {
+    fn from(value: &AuthState) -> Self {
+        let (name, message) = match value {
+            AuthState::Working(msg) => ("Working", msg.to_string()),
+            AuthState::Success(msg) => ("Success", msg.to_string()),
+            AuthState::Error(msg) => ("Error", msg.to_string()),
+        };
+        Self {
+            state: name.to_string(),
+            message,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AuthCredentials {
+    pub uri: String,
+    pub code: String,
+}
+
+/// When the cached tokens of an account expire, in seconds since the Unix epoch, or 0 if it
+/// isn't cached.
+///
+/// # Parameters
+/// * `uuid` - the Minecraft UUID of the account, not the ID of its client
+///
+/// # Errors
+/// * [`ClientworksError::InvalidId`] - if `uuid` isn't a valid UUID
+pub fn auth_validity(
+    state: &AppState,
+    uuid: String
+) -> Result<u64, ClientworksError> {
+    let uuid = parse_id(&uuid)?;
+    let ctx = state.api_context.lock().unwrap();
+    Ok(ctx.auth_cache.get_from_mc_uuid(&uuid).map_or(0, |cache| cache.expiration))
+}
+
+pub async fn recall_authentication<Scb>(
+    ctx: &AppState,
+    id: String,
+    state_callback: Scb
+) -> Result<bool, ClientworksError>
+where
+    Scb: FnMut(&AuthState),
+{
+    let uuid = parse_id(&id)?;
+
+    if cfg!(debug_assertions) { debug!("Recalling auth") }
+
+    if { let guard = ctx.api_context.lock().unwrap();
+        guard.controllers.get(&uuid).is_some() } {
+        if cfg!(debug_assertions) { debug!("Client is already authenticated.") }
+        Ok(true)
+    } else {
+        if cfg!(debug_assertions) { debug!("Client is not already authenticated.") }
+        if ctx.api_context.lock().unwrap().auth_cache.is_locked() {
+            return Err(ClientworksError::AuthCacheLocked)
+        }
+        let client_uuid: Uuid = {
+            let guard = ctx.api_context.lock().unwrap();
+            guard.clients.get_by_id(&uuid)
+                .map(|client| client.uuid)
+                .ok_or_else(|| {
+                    if cfg!(debug_assertions) { debug!("Client from provided ID is not registered.") }
+                    ClientworksError::ClientNotFound { id: id.clone() }
+                })?
+        };
+        let key = {
+            let guard = ctx.api_context.lock().unwrap();
+            guard.auth_cache.get_key_from_mc_uuid(&client_uuid)
+                .ok_or_else(|| {
+                    if cfg!(debug_assertions) { debug!("No authentication key is linked to the provided client's account.") }
+                    ClientworksError::AuthKeyMissing { uuid: client_uuid.to_string() }
+                })?.clone()
+        };
+
+        if cfg!(debug_assertions) { debug!("Auth key found in cache") }
+        cached_authentication(ctx.api_context.clone(), &key, state_callback).await
+            .map(|_| true)
+    }
+}
+
+pub fn auth_offline<Scb>(
+    state: &AppState,
+    username: String,
+    mut state_callback: Scb
+) -> Result<(String, MinecraftProfile), ClientworksError>
+where
+    Scb: FnMut(&AuthState),
+{
+    let mut ctx = state.api_context.lock().unwrap();
+    state_callback(&AuthState::Working("Verifying account...".to_string()));
+    if ctx.clients.get_by_username(&username).is_some() {
+        state_callback(&AuthState::Error(format!("Account {username} is already registered.")));
+        return Err(ClientworksError::ClientExists { username })
+    }
+    state_callback(&AuthState::Working("Offline account created.".to_string()));
+    let profile = MinecraftProfile::with_username(username.clone());
+    let id = crate::api::client::register(&mut ctx, &profile, AuthType::Offline)?;
+    let controller = ClientController::new(
+        id, username.clone(), profile.uuid,
+        Arc::new(AuthProtocol::Offline(username)),
+        ctx.scripts_dir(), ctx.events.clone()
+    );
+    ctx.controllers.add(controller);
+    Ok((id.to_string(), profile))
+}
+
+async fn cached_authentication<Scb>(
+    api_context: Arc<Mutex<ApiContext>>,
+    login_key: &String,
+    mut state_callback: Scb
+) -> Result<(String, MinecraftProfile), ClientworksError>
+where
+    Scb: FnMut(&AuthState),
+{
+    state_callback(&AuthState::Working("Looking for cache...".to_string()));
+    if api_context.lock().unwrap().auth_cache.is_locked() {
+        state_callback(&AuthState::Error("The authentication cache is locked.".to_string()));
+        return Err(ClientworksError::AuthCacheLocked)
+    }
+    let mut refresh_error = None;
+    let cache = {
+        let (cache, client, endpoints) = {
+            let guard = api_context.lock().unwrap();
+            (guard.auth_cache.0.get(login_key).cloned(), guard.http_client.clone(), guard.ms_endpoints.clone())
+        };
+        if let Some(cache) = cache {
+            if cache.has_expired() {
+                if cfg!(debug_assertions) { debug!("Cache expired, refreshing...") }
+                state_callback(&AuthState::Working("Cache expired, refresh is required.".to_string()));
+                match cache.refreshed(&client, &endpoints, &mut state_callback).await {
+                    Ok(refreshed) => {
+                        if cfg!(debug_assertions) { debug!("Token refreshed, all good.") }
+                        Some(refreshed)
+                    },
+                    Err(e) => {
+                        if cfg!(debug_assertions) { debug!("Failed to refresh authentication token.") }
+                        state_callback(&AuthState::Error(format!(
+                            "Failed to refresh authentication token, re-authentication is required: {e}"
+                        )));
+                        refresh_error = Some(e.to_string());
+                        None
+                    }
+                }
+            } else {
+                if cfg!(debug_assertions) { debug!("Cache is valid") }
+                state_callback(&AuthState::Working("Valid cache found.".to_string()));
+                if cfg!(debug_assertions) { debug!("Authentication from cache complete.") }
+                Some(cache.clone())
+            }
+        } else {
+            state_callback(&AuthState::Error("No cache found.".to_string()));
+            None
+        }
+    };
+    state_callback(&AuthState::Working("Validating cache...".to_string()));
+    if let Some(cache) = cache {
+        let client_id = {
+            let uuid = &cache.profile.uuid;
+            let mut guard = api_context.lock().unwrap();
+            if let Some(client) = guard.clients.get_by_mc_uuid(uuid) {
+                &client.id.clone()
+            } else {
+                state_callback(&AuthState::Working("Registering new client from cached profile...".to_string()));
+                &crate::api::client::register(&mut guard, &cache.profile, cache.auth_type())?
+            }
+        };
+        state_callback(&AuthState::Success("Cache successfully validated, authentication is allowed.".to_string()));
+        let mut guard = api_context.lock().unwrap();
+        let controller = ClientController::new_cached(&mut guard, client_id, &cache)?;
+        guard.controllers.add(controller);
+        let profile = cache.profile.clone();
+        guard.reauth_required.remove(login_key);
+        guard.auth_cache.0.insert(login_key.clone(), cache);
+        guard.auth_cache.write_to_file(&guard.save)?;
+        return Ok((client_id.to_string(), profile));
+    }
+    state_callback(&AuthState::Error("Account not found in cache.".to_string()));
+    Err(ClientworksError::AuthExpired { login_key: login_key.clone(), reason: refresh_error })
+}
+
+pub async fn auth_ms_cache<Scb>(
+    ctx: &AppState,
+    login_key: String,
+    state_callback: Scb
+) -> Result<(String, MinecraftProfile), ClientworksError>
+where
+    Scb: FnMut(&AuthState),
+{
+    match cached_authentication(ctx.api_context.clone(), &login_key, state_callback).await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(e)
+    }
+}
+
+pub async fn auth_ms_init<Scb>(
+    ctx: &AppState,
+    login_key: String,
+    state_callback: Scb
+) -> Result<AuthCredentials, ClientworksError>
+where
+    Scb: FnMut(&AuthState),
+{
+    let mut auth = {
+        let ctx = ctx.api_context.lock().unwrap();
+        auth::Authentication::with_client(ctx.http_client.clone(), ctx.ms_endpoints.clone())
+    };
+    auth.get_access_info(state_callback)
+    .await;
+
+    if let Some(credentials) = &auth.credentials {
+        let (uri, code) = (credentials.uri.clone(), credentials.code.clone());
+        println!("{credentials:#?}");
+        ctx.api_context.lock().unwrap().ongoing_auths.insert(login_key, auth);
+        Ok(AuthCredentials { uri, code })
+    } else {
+        Err(ClientworksError::AuthFailed { reason: auth.state.to_string() })
+    }
+}
+
+pub async fn auth_ms_finish<Scb>(
+    ctx: &AppState,
+    login_key: String,
+    register: bool,
+    mut state_callback: Scb
+) -> Result<(String, MinecraftProfile), ClientworksError>
+where
+    Scb: FnMut(&AuthState),
+{
+    let mut auth = {
+        let mut ctx_guard = ctx.api_context.lock().unwrap();
+        // the new account could not be cached
+        if register && ctx_guard.auth_cache.is_locked() {
+            return Err(ClientworksError::AuthCacheLocked)
+        }
+        ctx_guard.ongoing_auths.remove(&login_key)
+    };
+
+    if let Some(mut auth) = auth {
+        auth.authenticate_ms(Default::default(), &mut state_callback)
+        .await;
+        auth.authenticate_minecraft(&mut state_callback)
+        .await;
+
+        if let Some(token) = &auth.access_token {
+            if let Some(profile) = auth.profile {
+                let id = {
+                    let mut ctx = ctx.api_context.lock().unwrap();
+                    if register {
+                        let msa = auth.msa.unwrap();
+                        let cache = MinecraftAuthCache {
+                            access_token: token.access_token.clone(),
+                            credentials: Credentials::Microsoft { msa: msa.clone() },
+                            expiration: token.expires_at,
+                            profile: profile.clone()
+                        };
+                        ctx.reauth_required.remove(&login_key);
+                        ctx.auth_cache.0.insert(login_key.clone(), cache);
+                        ctx.auth_cache.write_to_file(&ctx.save)?;
+                        let id = crate::api::client::register(&mut ctx, &profile, AuthType::Microsoft)?;
+                        let controller = ClientController::new(
+                            id, profile.username.clone(), profile.uuid,
+                            Arc::new(AuthProtocol::Microsoft(
+                                token.access_token.clone(),
+                                Box::new(msa), Box::new(profile.clone())
+                            )),
+                            ctx.scripts_dir(), ctx.events.clone()
+                        );
+                        ctx.controllers.add(controller);
+                        id.to_string()
+                    } else {
+                        "".to_string()
+                    }
+                };
+                Ok((id, profile))
+            } else {
+                Err(ClientworksError::ProfileMissing)
+            }
+        } else {
+            Err(ClientworksError::AuthFailed { reason: auth.state.to_string() })
+        }
+    } else {
+        Err(ClientworksError::NoOngoingAuth { login_key })
+    }
+}
+
+/// Logs into an account of a Yggdrasil-compatible authentication server with its username, or
+/// email, and password, then registers it as a client unless it already is. Its tokens are
+/// cached under `login_key`, the password isn't stored.
+///
+/// # Errors
+/// * [`ClientworksError::AuthCacheLocked`] - if the authentication cache is locked
+/// * [`ClientworksError::AuthFailed`] - if the authentication server rejected the credentials
+/// * [`ClientworksError::ClientExists`] - if another client with the same username is registered
+pub async fn auth_yggdrasil<Scb>(
+    ctx: &AppState,
+    login_key: String,
+    server: YggdrasilServer,
+    username: String,
+    password: String,
+    mut state_callback: Scb
+) -> Result<(String, MinecraftProfile), ClientworksError>
+where
+    Scb: FnMut(&AuthState),
+{
+    let client = {
+        let api = ctx.api_context.lock().unwrap();
+        if api.auth_cache.is_locked() {
+            return Err(ClientworksError::AuthCacheLocked)
+        }
+        api.http_client.clone()
+    };
+    state_callback(&AuthState::Working(format!("Authenticating with {}...", server.auth_url)));
+    let (token, profile) = match yggdrasil::authenticate(&client, &server, &username, &password).await {
+        Ok(result) => result,
+        Err(reason) => {
+            state_callback(&AuthState::Error(reason.clone()));
+            return Err(ClientworksError::AuthFailed { reason })
+        }
+    };
+    state_callback(&AuthState::Success(token.access_token.clone()));
+
+    let mut ctx = ctx.api_context.lock().unwrap();
+    let id = match ctx.clients.get_by_mc_uuid(&profile.uuid) {
+        Some(client) => client.id,
+        None => crate::api::client::register(&mut ctx, &profile, AuthType::Yggdrasil)?
+    };
+    let cache = MinecraftAuthCache::yggdrasil(server, token, profile.clone());
+    if let Some(controller) = ctx.controllers.get_mut(&id) {
+        controller.set_auth(Arc::new(cache.protocol()));
+    } else {
+        let controller = ClientController::new_cached(&mut ctx, &id, &cache)?;
+        ctx.controllers.add(controller);
+    }
+    ctx.reauth_required.remove(&login_key);
+    ctx.auth_cache.0.insert(login_key, cache);
+    ctx.auth_cache.write_to_file(&ctx.save)?;
+    Ok((id.to_string(), profile))
+}
+
+pub fn get_auth_cache_state(state: &AppState) -> AuthCacheState {
+    state.api_context.lock().unwrap().auth_cache.state()
+}
+
+/// Unlocks the encrypted authentication cache, allowing Microsoft clients to authenticate
+/// from it. Does nothing if the cache isn't locked.
+///
+/// # Errors
+/// * [`ClientworksError::InvalidPassphrase`] - if the passphrase is wrong
+/// * [`ClientworksError::Io`] - if the encrypted cache is corrupted
+pub fn unlock_auth_cache(state: &AppState, passphrase: String) -> Result<(), ClientworksError> {
+    state.api_context.lock().unwrap().auth_cache.unlock(&passphrase)
+}
+
+/// Sets, changes or removes the passphrase the authentication cache is encrypted with, then
+/// writes it again. Setting a passphrase on a plaintext cache encrypts it in place.
+///
+/// # Parameters
+/// * `current` - the current passphrase, required if the cache is encrypted
+/// * `new` - the new passphrase, or `None` to store the cache as plaintext
+///
+/// # Errors
+/// * [`ClientworksError::AuthCacheLocked`] - if the cache hasn't been unlocked
+/// * [`ClientworksError::InvalidPassphrase`] - if `current` is wrong, or `new` is empty
+pub fn set_auth_passphrase(
+    state: &AppState,
+    current: Option<String>,
+    new: Option<String>
+) -> Result<(), ClientworksError> {
+    let mut ctx = state.api_context.lock().unwrap();
+    ctx.auth_cache.set_passphrase(current.as_deref(), new.as_deref())?;
+    match new {
+        Some(_) => info!("Authentication cache is now encrypted"),
+        None => info!("Authentication cache is now stored as plaintext"),
+    }
+    ctx.auth_cache.write_to_file(&ctx.save)
+}
+
+/// Saves refreshed tokens to the authentication cache, and hands them to the controller of
+/// the account, if any.
+fn store_refreshed(api: &mut ApiContext, login_key: &str, cache: MinecraftAuthCache) {
+    let client_id = api.clients.get_by_mc_uuid(&cache.profile.uuid).map(|client| client.id);
+    if let Some(controller) = client_id.and_then(|id| api.controllers.get_mut(&id)) {
+        controller.set_auth(Arc::new(cache.protocol()));
+    }
+    api.reauth_required.remove(login_key);
+    api.auth_cache.0.insert(login_key.to_string(), cache);
+    if let Err(e) = api.auth_cache.write_to_file(&api.save) {
+        error!("Failed to save refreshed tokens of {login_key}: {e}");
+    }
+}
+
+/// Refreshes the tokens of every cached account expiring within [`REFRESH_MARGIN`], updating
+/// the controllers of the accounts and the authentication cache.
+///
+/// Failures are retried on the next scan until the tokens have expired. Then, the account is
+/// reported once with a [`Payload::ReauthRequired`] event, and skipped until it authenticates
+/// again.
+pub async fn refresh_expiring_tokens(state: &AppState) {
+    let (expiring, client, endpoints): (Vec<(String, MinecraftAuthCache)>, _, _) = {
+        let ctx = state.api_context.lock().unwrap();
+        let expiring = ctx.auth_cache.0.iter()
+            .filter(|(key, cache)| !ctx.reauth_required.contains(*key) && cache.expires_within(REFRESH_MARGIN))
+            .map(|(key, cache)| (key.clone(), cache.clone()))
+            .collect();
+        (expiring, ctx.http_client.clone(), ctx.ms_endpoints.clone())
+    };
+    for (login_key, cache) in expiring {
+        let username = cache.profile.username.clone();
+        match cache.refreshed(&client, &endpoints, |_| {}).await {
+            Ok(refreshed) => {
+                info!("Refreshed the tokens of {username}, valid until {}", refreshed.expiration);
+                store_refreshed(&mut state.api_context.lock().unwrap(), &login_key, refreshed);
+            }
+            Err(reason) if cache.has_expired() => {
+                warn!("Tokens of {username} have expired, re-authentication is required: {reason}");
+                let (client_id, events) = {
+                    let mut ctx = state.api_context.lock().unwrap();
+                    ctx.reauth_required.insert(login_key.clone());
+                    let client_id = ctx.clients.get_by_mc_uuid(&cache.profile.uuid).map(|client| client.id);
+                    (client_id, ctx.events.clone())
+                };
+                if let Some(client_id) = client_id {
+                    Publisher::new(client_id, events)
+                        .publish(Payload::ReauthRequired { login_key, username, reason })
+                        .await;
+                }
+            }
+            Err(reason) => warn!("Failed to refresh the tokens of {username}, retrying later: {reason}"),
+        }
+    }
+}
+
+/// Refreshes the expiring tokens of the authentication cache every `interval`, forever, see
+/// [`refresh_expiring_tokens`]. Meant to be spawned once by long-running frontends.
+pub async fn refresh_tokens(state: AppState, interval: Duration) {
+    loop {
+        refresh_expiring_tokens(&state).await;
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use uuid::Uuid;
+    use crate::api::auth::{AuthCache, AuthCacheState};
+
+    #[test]
+    fn unreadable_cache_is_kept() {
+        let dir = std::env::temp_dir().join(format!("clientworks-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        // an encrypted cache cut short by an interrupted write
+        let truncated = r#"{"version":1,"salt":"AAAA"#;
+        fs::write(dir.join("auth_cache.json"), truncated).unwrap();
+
+        let cache = AuthCache::from_file(&dir);
+        assert_eq!(cache.state(), AuthCacheState::Unreadable);
+        assert!(cache.write_to_file(&dir).is_err());
+        assert_eq!(fs::read_to_string(dir.join("auth_cache.json")).unwrap(), truncated);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
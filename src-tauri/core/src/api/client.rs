@@ -17,11 +17,13 @@ use std::{
 use azalea::ecs::error::warn;
 use uuid::Uuid;
 use crate::{
-    api::{ApiContext, Server},
+    api::{ApiContext, ClientworksError, Server},
     client::{
         auth::MinecraftProfile,
         ClientController,
-        Version
+        Version,
+        network::ReconnectPolicy,
+        proxy::Proxy
     }
 };
 
@@ -29,14 +31,29 @@ use crate::{
 pub struct ClientConnection {
     pub id: Uuid,
     pub version: Version,
-    pub server: Server
+    pub server: Server,
+    #[serde(default)]
+    pub reconnect: ReconnectPolicy,
+    /// The file name of the script run by the instance, in the scripts directory
+    #[serde(default)]
+    pub script: Option<String>,
+    /// The prefix of the instance's client-side commands, if not the default one
+    #[serde(default)]
+    pub command_prefix: Option<String>,
+    /// The proxy of the instance, overriding the client's
+    #[serde(default)]
+    pub proxy: Option<Proxy>
 }
 
 impl ClientConnection {
     pub fn new(id: Uuid, version: Version, target: Server) -> Self {
         Self {
             id, version,
-            server: target
+            server: target,
+            reconnect: ReconnectPolicy::default(),
+            script: None,
+            command_prefix: None,
+            proxy: None
         }
     }
 }
@@ -47,7 +64,10 @@ pub struct Client {
     pub username: String,
     pub uuid: Uuid,
     pub auth: AuthType,
-    pub connections: HashMap<Uuid, ClientConnection>
+    pub connections: HashMap<Uuid, ClientConnection>,
+    /// The proxy of the client's instances, unless they have their own
+    #[serde(default)]
+    pub proxy: Option<Proxy>
 }
 
 impl Client {
@@ -57,7 +77,8 @@ impl Client {
             username,
             uuid,
             auth,
-            connections: HashMap::new()
+            connections: HashMap::new(),
+            proxy: None
         }
     }
 }
@@ -66,13 +87,16 @@ impl Client {
 pub enum AuthType {
     Offline,
     Microsoft,
+    /// An account of a Yggdrasil-compatible authentication server, see
+    /// [`crate::client::yggdrasil::YggdrasilServer`]
+    Yggdrasil,
 }
 
-fn save(api: &mut ApiContext) -> Result<(), String> {
+fn save(api: &mut ApiContext) -> Result<(), ClientworksError> {
     match api.clients.write_to_file(&api.save) {
         Err(e) => {
             warn!("Failed to write client list: {e}");
-            Err(e.to_string())
+            Err(e.into())
         },
         Ok(_) => Ok(())
     }
@@ -82,34 +106,27 @@ fn save(api: &mut ApiContext) -> Result<(), String> {
 ///
 /// # Parameters
 /// * `profile` - the [`MinecraftProfile`] to create the account from
+/// * `auth` - how the account authenticates
 ///
 /// # Errors
-/// * `Client already exists` - if the client already exists
-/// * `Failed to write client list` - if the client list could not be saved
+/// * [`ClientworksError::ClientExists`] - if the client already exists
+/// * [`ClientworksError::Io`] - if the client list could not be saved
 ///
 /// # Returns
 /// The randomly-generated v4 UUID the new client is bound to
-pub fn register(api: &mut ApiContext, profile: &MinecraftProfile) -> Result<Uuid, String> {
+pub fn register(api: &mut ApiContext, profile: &MinecraftProfile, auth: AuthType) -> Result<Uuid, ClientworksError> {
     if api.clients.get_by_username(&profile.username).is_some() {
-        return Err(format!("Client {} already exists", profile.username));
+        return Err(ClientworksError::ClientExists { username: profile.username.clone() });
     }
     info!("Creating client {}", profile.username);
     let id = Uuid::new_v4();
     api.clients.0
-        .insert(id, Client::new(id, profile.username.clone(),
-                                profile.uuid, {
-                                    if profile.authenticated {
-                                        AuthType::Microsoft
-                                    } else {
-                                        AuthType::Offline
-                                    }
-                                })
-        );
+        .insert(id, Client::new(id, profile.username.clone(), profile.uuid, auth));
     save(api)?;
     Ok(id)
 }
 
-pub fn unregister(api: &mut ApiContext, uuid: String) -> Result<(), String> {
+pub fn unregister(api: &mut ApiContext, uuid: String) -> Result<(), ClientworksError> {
     let client_id: Option<Uuid> = {
         let client = api.clients.get_by_mc_uuid(
             &Uuid::from_str(&uuid).unwrap_or_default()
@@ -125,7 +142,7 @@ pub fn unregister(api: &mut ApiContext, uuid: String) -> Result<(), String> {
         api.clients.0.remove(&id);
         save(api)
     } else {
-        Err(format!("Client {uuid} does not exist"))
+        Err(ClientworksError::ClientNotFound { id: uuid })
     }
 }
 
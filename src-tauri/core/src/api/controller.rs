@@ -0,0 +1,482 @@
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    fs, io,
+    path::Path,
+    str::FromStr
+};
+use std::sync::Arc;
+use log::info;
+use serde::Serialize;
+use uuid::Uuid;
+use crate::{
+    AppState,
+    api::{
+        ApiContext, ClientworksError,
+        client::ClientConnection,
+        parse_id
+    },
+    client::{
+        self, Version, InstanceEndError,
+        ClientInstance,
+        hooks::{chatlog::ChatLine, logs::{self, LogEntry}}
+    }
+};
+use crate::api::client::AuthType;
+use crate::client::{AuthProtocol, ClientController};
+use crate::client::auth::MinecraftProfile;
+use crate::client::movement::Movement;
+use crate::client::telemetry::Telemetry;
+use crate::client::tablist::TabList;
+use crate::client::proxy::Proxy;
+use crate::client::commands::{self as instance_commands, Input};
+use crate::client::network::{ConnectionState, ReconnectPolicy};
+// Where present, the ID and KEY parameters represent the UUID of the client and controller, respectively.
+
+/// Resolves the version of a new connection, from its name or from the status of the server
+/// when given [`client::AUTO_VERSION`].
+async fn resolve_version(
+    state: &AppState,
+    server_name: &str,
+    version: &str
+) -> Result<Version, ClientworksError> {
+    if !version.eq_ignore_ascii_case(client::AUTO_VERSION) {
+        return Version::from_string(version)
+            .ok_or_else(|| ClientworksError::InvalidVersion { version: version.to_string() })
+    }
+    let cached = crate::api::get_server_status(state, server_name.to_string(), false).await?;
+    let status = cached.status.ok_or_else(|| ClientworksError::ServerUnreachable {
+        name: server_name.to_string(),
+        reason: cached.error.unwrap_or_default()
+    })?;
+    let detected = Version::from_protocol(status.protocol)
+        .ok_or_else(|| ClientworksError::UnsupportedVersion {
+            name: server_name.to_string(),
+            version: status.version,
+            protocol: status.protocol
+        })?;
+    info!("Detected version {detected} for server {server_name}");
+    Ok(detected)
+}
+
+/// Creates an instance of a client on a saved server, and registers its connection in the
+/// client list. `version` is either the name of a version, see [`get_available_versions`],
+/// or [`client::AUTO_VERSION`] to use the version the server reports when pinged.
+///
+/// The instance goes through `proxy` if given, see [`crate::api::proxy::resolve`], and through
+/// the client's proxy otherwise.
+///
+/// # Errors
+/// * [`ClientworksError::InvalidVersion`] - if the version isn't supported
+/// * [`ClientworksError::ServerUnreachable`] - if the version is detected, but the server
+///   can't be pinged
+/// * [`ClientworksError::UnsupportedVersion`] - if the server runs a version that isn't supported
+/// * [`ClientworksError::ProxyNotFound`] / [`ClientworksError::InvalidProxy`] /
+///   [`ClientworksError::ProxyPoolEmpty`] - if the proxy can't be resolved
+pub async fn create_connection(
+    state: &AppState,
+    id: String,
+    server_name: String,
+    version: String,
+    proxy: Option<String>
+) -> Result<String, ClientworksError> {
+    let uuid = parse_id(&id)?;
+    if state.api_context.lock().unwrap().servers.get_server(&server_name).is_none() {
+        return Err(ClientworksError::ServerNotFound { name: server_name })
+    }
+    let version = resolve_version(state, &server_name, &version).await?;
+    let instance_id: String = {
+        let mut ctx = state.api_context.lock().unwrap();
+        let mut server = ctx.servers.get_server(&server_name)
+            .ok_or_else(|| ClientworksError::ServerNotFound { name: server_name.clone() })?.clone();
+        let proxy = proxy.map(|proxy| crate::api::proxy::resolve(&mut ctx, &proxy)).transpose()?;
+        let mut controller = ctx.controllers.get_mut(&uuid)
+            .ok_or_else(|| ClientworksError::ControllerMissing { id: id.clone() })?;
+        let id = controller.create_instance(server.clone(), Some(version.clone()));
+        if let (Some(proxy), Some(instance)) = (&proxy, controller.get_instance_mut(&id)) {
+            instance.proxy = Some(proxy.clone());
+        }
+        {
+            let mut conn = ClientConnection::new(
+                id, version, server.clone()
+            );
+            conn.proxy = proxy;
+            let mut client = ctx.clients.get_mut_by_id(&uuid).unwrap();
+            client.connections.insert(conn.id, conn);
+        }
+        id.to_string()
+    };
+    let ctx = state.api_context.lock().unwrap();
+    ctx.clients.write_to_file(&ctx.save)?;
+    Ok(instance_id)
+}
+
+/// Makes sure a controller exists for the client with the given ID.
+///
+/// Offline clients don't require any authentication, so their controller is created on the
+/// fly. Microsoft and Yggdrasil clients must be authenticated beforehand, see
+/// [`crate::api::auth::recall_authentication`].
+///
+/// # Errors
+/// * [`ClientworksError::ClientNotFound`] - if no client is registered with the given ID
+/// * [`ClientworksError::ControllerMissing`] - if the client is an account that isn't authenticated
+pub fn ensure_controller(api: &mut ApiContext, id: &Uuid) -> Result<(), ClientworksError> {
+    if api.controllers.contains(id) {
+        return Ok(())
+    }
+    let client = api.clients.get_by_id(id)
+        .cloned()
+        .ok_or_else(|| ClientworksError::ClientNotFound { id: id.to_string() })?;
+    if client.auth != AuthType::Offline {
+        return Err(ClientworksError::ControllerMissing { id: id.to_string() })
+    }
+    let profile = MinecraftProfile::with_username(client.username.clone());
+    let controller = ClientController::new_offline(api, &client.id, &profile)?;
+    api.controllers.add(controller);
+    Ok(())
+}
+
+/// The instances of a client with their connection state, by instance key. Their proxies are
+/// returned without their password.
+pub fn get_instances(
+    state: &AppState,
+    id: String
+) -> Result<HashMap<String, (ConnectionState, ClientConnection)>, ClientworksError> {
+    let mut ctx = state.api_context.lock().unwrap();
+    let uuid = parse_id(&id)?;
+    let client = {
+        ctx.clients.get_by_id(&uuid).cloned()
+    };
+    if let Some(client) = client {
+        ensure_controller(&mut ctx, &uuid)?;
+        let controller = ctx.controllers.get(&uuid).unwrap();
+
+        let map = {
+            let mut map = HashMap::new();
+            for (id, instance) in controller.instances.iter() {
+                let connection = client.connections.get(id);
+                if let Some(connection) = connection {
+                    let mut connection = connection.clone();
+                    connection.proxy = connection.proxy.as_ref().map(Proxy::redacted);
+                    map.insert(id.to_string(), (instance.state(), connection));
+                }
+            }
+            map
+        };
+
+        return Ok(map)
+    }
+    Err(ClientworksError::ClientNotFound { id })
+}
+
+/// Sets the reconnect policy of an instance and saves it to the client list.
+///
+/// Takes effect from the next time the instance connects.
+pub fn set_reconnect_policy(
+    state: &AppState,
+    id: String, key: String,
+    policy: ReconnectPolicy
+) -> Result<(), ClientworksError> {
+    let key = parse_id(&key)?;
+    let uuid = parse_id(&id)?;
+    let mut ctx = state.api_context.lock().unwrap();
+    locate_instance(&mut ctx, id.clone(), &key)?.reconnect = policy.clone();
+    let connection = ctx.clients.get_mut_by_id(&uuid)
+        .and_then(|client| client.connections.get_mut(&key))
+        .ok_or_else(|| ClientworksError::InstanceNotFound { id, key: key.to_string() })?;
+    connection.reconnect = policy;
+    ctx.clients.write_to_file(&ctx.save)?;
+    Ok(())
+}
+
+/// Sets the script run by an instance, by its file name in the scripts directory, and saves
+/// it to the client list. Pass `None` to stop running any script.
+///
+/// Takes effect from the next time the instance connects; the script itself is reloaded
+/// whenever its file changes.
+///
+/// # Errors
+/// * [`ClientworksError::ScriptNotFound`] - if there is no such file in the scripts directory
+pub fn set_instance_script(
+    state: &AppState,
+    id: String, key: String,
+    script: Option<String>
+) -> Result<(), ClientworksError> {
+    let key = parse_id(&key)?;
+    let uuid = parse_id(&id)?;
+    let mut ctx = state.api_context.lock().unwrap();
+    let path = match &script {
+        // only plain file names, so that scripts can't be loaded from anywhere else
+        Some(name) if Path::new(name).file_name() != Some(OsStr::new(name))
+            || !ctx.scripts_dir().join(name).is_file() => {
+            return Err(ClientworksError::ScriptNotFound { name: name.clone() })
+        }
+        Some(name) => Some(ctx.scripts_dir().join(name)),
+        None => None
+    };
+    locate_instance(&mut ctx, id.clone(), &key)?.script = path;
+    let connection = ctx.clients.get_mut_by_id(&uuid)
+        .and_then(|client| client.connections.get_mut(&key))
+        .ok_or_else(|| ClientworksError::InstanceNotFound { id, key: key.to_string() })?;
+    connection.script = script;
+    ctx.clients.write_to_file(&ctx.save)?;
+    Ok(())
+}
+
+/// Sets the prefix of the client-side commands of an instance, such as `.list`, and saves it
+/// to the client list. Pass `None` to go back to the default prefix.
+///
+/// # Errors
+/// * [`ClientworksError::InvalidCommandPrefix`] - if the prefix is empty, contains whitespace
+///   or starts with `/`, which would shadow server commands
+pub fn set_command_prefix(
+    state: &AppState,
+    id: String, key: String,
+    prefix: Option<String>
+) -> Result<(), ClientworksError> {
+    let key = parse_id(&key)?;
+    let uuid = parse_id(&id)?;
+    let invalid = prefix.as_ref().filter(|prefix| {
+        prefix.is_empty() || prefix.contains(char::is_whitespace) || prefix.starts_with('/')
+    });
+    if let Some(prefix) = invalid {
+        return Err(ClientworksError::InvalidCommandPrefix { prefix: prefix.clone() })
+    }
+    let mut ctx = state.api_context.lock().unwrap();
+    locate_instance(&mut ctx, id.clone(), &key)?.commands_mut()
+        .set_prefix(prefix.clone().unwrap_or_else(|| instance_commands::DEFAULT_PREFIX.to_string()));
+    let connection = ctx.clients.get_mut_by_id(&uuid)
+        .and_then(|client| client.connections.get_mut(&key))
+        .ok_or_else(|| ClientworksError::InstanceNotFound { id, key: key.to_string() })?;
+    connection.command_prefix = prefix;
+    ctx.clients.write_to_file(&ctx.save)?;
+    Ok(())
+}
+
+/// The file names of all scripts in the scripts directory, sorted.
+pub fn get_scripts(state: &AppState) -> Result<Vec<String>, ClientworksError> {
+    let dir = state.api_context.lock().unwrap().scripts_dir();
+    let mut scripts: Vec<String> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_file())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e.into())
+    };
+    scripts.sort();
+    Ok(scripts)
+}
+
+/// The maximum number of log entries returned by [`get_logs`] at once.
+const LOG_PAGE_LIMIT: usize = 1000;
+
+/// Pages through the persisted logs of an instance, within `from..to` (milliseconds since
+/// the Unix epoch, unbounded if omitted), oldest first, skipping the first `offset` entries
+/// at `from`.
+///
+/// Request the next page from the timestamp of the last entry returned, with the number of
+/// entries returned with that timestamp as the offset, plus the current offset if they all
+/// had it.
+pub fn get_logs(
+    state: &AppState,
+    id: String, key: String,
+    from: Option<i64>, offset: Option<usize>, to: Option<i64>,
+    limit: Option<usize>
+) -> Result<Vec<LogEntry>, ClientworksError> {
+    let key = parse_id(&key)?;
+    let uuid = parse_id(&id)?;
+    let dir = {
+        let ctx = state.api_context.lock().unwrap();
+        let client = ctx.clients.get_by_id(&uuid)
+            .ok_or_else(|| ClientworksError::ClientNotFound { id: id.clone() })?;
+        if !client.connections.contains_key(&key) {
+            return Err(ClientworksError::InstanceNotFound { id, key: key.to_string() })
+        }
+        ctx.logs_dir().join(key.to_string())
+    };
+    let limit = limit.unwrap_or(LOG_PAGE_LIMIT).clamp(1, LOG_PAGE_LIMIT);
+    Ok(logs::read(&dir, from.unwrap_or(i64::MIN), offset.unwrap_or(0), to.unwrap_or(i64::MAX), limit)?)
+}
+
+pub fn get_available_versions() -> Vec<Version> {
+    Version::all()
+}
+
+pub(crate) fn locate_instance<'a>(
+    api: &'a mut ApiContext,
+    id: String, key: &Uuid
+) -> Result<&'a mut ClientInstance, ClientworksError> {
+    let mut controller = {
+        let uuid = parse_id(&id)?;
+        api.controllers.get_mut(&uuid)
+            .ok_or_else(|| ClientworksError::ControllerMissing { id: id.clone() })?
+    };
+    controller.get_instance_mut(key)
+        .ok_or_else(|| ClientworksError::InstanceNotFound { id, key: key.to_string() })
+}
+
+pub fn send_chat(
+    state: &AppState,
+    id: String, key: String,
+    message: String
+) -> Result<(), ClientworksError> {
+    let key = parse_id(&key)?;
+    let mut ctx = state.api_context.lock().unwrap();
+    let mut instance = locate_instance(&mut ctx, id, &key)?;
+    {
+        // client-side commands may run while offline, e.g. '.reconnect'
+        let command = matches!(instance.commands().parse(&message), Input::Command { .. });
+        if !instance.is_running() && !command {
+            return Err(ClientworksError::InstanceOffline { key: key.to_string() });
+        }
+        instance.send_message(message);
+    }
+    Ok(())
+}
+
+/// The connection state of an instance, along with its in-game state while it is playing.
+#[derive(Serialize, Debug)]
+pub struct InstanceStatus {
+    pub state: ConnectionState,
+    pub telemetry: Option<Telemetry>,
+}
+
+pub fn get_instance_status(
+    state: &AppState,
+    id: String, key: String
+) -> Result<InstanceStatus, ClientworksError> {
+    let key = parse_id(&key)?;
+    let mut ctx = state.api_context.lock().unwrap();
+    let instance = locate_instance(&mut ctx, id, &key)?;
+    Ok(InstanceStatus {
+        state: instance.state(),
+        telemetry: instance.telemetry()
+    })
+}
+
+/// The tab list of the server an instance is playing on. Players joining and leaving are
+/// also published as [`crate::client::hooks::Payload::PlayerJoin`] and
+/// [`crate::client::hooks::Payload::PlayerLeave`] events.
+pub fn get_tab_list(
+    state: &AppState,
+    id: String, key: String
+) -> Result<TabList, ClientworksError> {
+    let key = parse_id(&key)?;
+    let mut ctx = state.api_context.lock().unwrap();
+    let instance = locate_instance(&mut ctx, id, &key)?;
+    Ok(instance.tab_list())
+}
+
+/// Sends a movement command to an instance, applied on its next tick. Its progress and
+/// failure are published as [`crate::client::hooks::Payload::Movement`] events.
+pub fn send_movement(
+    state: &AppState,
+    id: String, key: String,
+    movement: Movement
+) -> Result<(), ClientworksError> {
+    let key = parse_id(&key)?;
+    let mut ctx = state.api_context.lock().unwrap();
+    let instance = locate_instance(&mut ctx, id, &key)?;
+    if !instance.is_running() {
+        return Err(ClientworksError::InstanceOffline { key: key.to_string() });
+    }
+    instance.send_movement(movement);
+    Ok(())
+}
+
+/// The chat lines of an instance received after the sequence number `seq`, oldest first,
+/// as far as the instance's chat buffer goes back. Pass `0` to get the whole buffer.
+///
+/// Meant for frontends to backfill the chat they missed while they weren't listening.
+pub fn get_chat_since(
+    state: &AppState,
+    id: String, key: String,
+    seq: u64
+) -> Result<Vec<ChatLine>, ClientworksError> {
+    let key = parse_id(&key)?;
+    let mut ctx = state.api_context.lock().unwrap();
+    let instance = locate_instance(&mut ctx, id, &key)?;
+    Ok(instance.chat().since(seq))
+}
+
+pub fn connect_client(
+    state: &AppState,
+    id: String, key: String
+) -> Result<(), ClientworksError> {
+    let key = parse_id(&key)?;
+    let mut ctx = state.api_context.lock().unwrap();
+    let mut instance = locate_instance(&mut ctx, id, &key)?;
+    instance.chat().push("Received connect command...".to_string());
+    instance.connect();
+    Ok(())
+}
+
+pub async fn disconnect_client(
+    state: &AppState,
+    id: String, key: String
+) -> Result<(), ClientworksError> {
+    let key = parse_id(&key)?;
+    {
+        let mut ctx = state.api_context.lock().unwrap();
+        let mut instance = locate_instance(&mut ctx, id, &key)?;
+        instance.chat().push("Received disconnect command...".to_string());
+        instance.disconnect_notify()
+            .map_err(|e| ClientworksError::instance_end(&key, e))?;
+        // instance.disconnect()?;
+    }
+    Ok(())
+}
+
+pub async fn kill_client_soft(
+    state: &AppState,
+    id: String, key: String
+) -> Result<(), ClientworksError> {
+    let key = parse_id(&key)?;
+    let (key, mut handle) = {
+        let mut ctx = state.api_context.lock().unwrap();
+        let mut instance = locate_instance(&mut ctx, id.clone(), &key)?;
+        instance.chat().push("Received soft-kill command...".to_string());
+        instance.disconnect_notify()
+            .map_err(|e| ClientworksError::instance_end(&key, e))?;
+        (instance.id, instance.client_thread.take())
+    };
+    match client::soft_kill(&key, &mut handle).await {
+        Err(InstanceEndError::Timeout) => {
+            // The thread didn't close in time: hand it back to the instance and hard-kill it.
+            // If the instance is gone by now, dropping its handle can't abort it, so do it here.
+            let mut ctx = state.api_context.lock().unwrap();
+            match locate_instance(&mut ctx, id, &key) {
+                Ok(instance) => {
+                    instance.client_thread = handle;
+                    instance.chat().push("Soft-kill timed out, hard-killing...".to_string());
+                    instance.kill()
+                        .map_err(|e| ClientworksError::instance_end(&key, e))
+                }
+                Err(err) => {
+                    if let Some(handle) = handle {
+                        handle.abort();
+                    }
+                    Err(err)
+                }
+            }
+        }
+        result => result.map_err(|e| ClientworksError::instance_end(&key, e))
+    }
+}
+
+pub async fn kill_client(
+    state: &AppState,
+    id: String, key: String
+) -> Result<(), ClientworksError> {
+    let key = parse_id(&key)?;
+    {
+        let mut ctx = state.api_context.lock().unwrap();
+        let mut instance = locate_instance(&mut ctx, id, &key)?;
+        instance.chat().push("Received hard-kill command...".to_string());
+        instance.kill()
+            .map_err(|e| ClientworksError::instance_end(&key, e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use std::{fmt, io};
+use serde::Serialize;
+use uuid::Uuid;
+use crate::client::InstanceEndError;
+
+/// The error returned by all commands of the command layer.
+///
+/// Serialized with a stable `code` tag and its context fields, e.g.
+/// `{ "code": "ClientNotFound", "id": "..." }`, so that the frontend and any API consumer
+/// can branch on the kind of error and present it however they see fit.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "code")]
+pub enum ClientworksError {
+    /// A provided ID could not be parsed as a UUID.
+    InvalidId { id: String },
+    /// No client is registered with the given ID, username or Minecraft UUID.
+    ClientNotFound { id: String },
+    /// A client with the same username is already registered.
+    ClientExists { username: String },
+    /// No server is saved under the given name.
+    ServerNotFound { name: String },
+    /// The server could not be pinged, e.g. to detect its version.
+    ServerUnreachable { name: String, reason: String },
+    /// The given Minecraft version isn't supported.
+    InvalidVersion { version: String },
+    /// The server runs a version that isn't supported, whose protocol number is `protocol`.
+    UnsupportedVersion { name: String, version: String, protocol: i32 },
+    /// A server with the same name already exists.
+    ServerExists { name: String },
+    /// No swarm is saved under the given name.
+    SwarmNotFound { name: String },
+    /// A swarm with the same name already exists.
+    SwarmExists { name: String },
+    /// No macro is saved under the given name.
+    MacroNotFound { name: String },
+    /// The macro can't be run as is, e.g. because of an invalid chat pattern.
+    InvalidMacro { name: String, reason: String },
+    /// No macro run is ongoing with the given ID.
+    MacroRunNotFound { run: String },
+    /// No script with the given file name exists in the scripts directory.
+    ScriptNotFound { name: String },
+    /// No proxy is saved under the given name.
+    ProxyNotFound { name: String },
+    /// A proxy with the same name already exists.
+    ProxyExists { name: String },
+    /// The proxy URL can't be parsed, or the name is reserved.
+    InvalidProxy { proxy: String, reason: String },
+    /// A proxy was requested from the proxy pool, but no proxy is saved.
+    ProxyPoolEmpty,
+    /// The prefix of client-side commands can't be empty, contain whitespace or start with `/`.
+    InvalidCommandPrefix { prefix: String },
+    /// The client exists, but has no controller; Microsoft and Yggdrasil clients must authenticate first.
+    ControllerMissing { id: String },
+    /// The client controller has no instance with the given key.
+    InstanceNotFound { id: String, key: String },
+    /// The instance is not connected.
+    InstanceOffline { key: String },
+    /// The instance's client thread did not finish in time.
+    InstanceTimeout { key: String },
+    /// No authentication key is linked to the account in the authentication cache.
+    AuthKeyMissing { uuid: String },
+    /// The authentication cache is encrypted and hasn't been unlocked with its passphrase yet.
+    AuthCacheLocked,
+    /// The passphrase of the authentication cache is wrong, or can't be used.
+    InvalidPassphrase { reason: String },
+    /// The cached tokens have expired and could not be refreshed; re-authentication is required.
+    AuthExpired { login_key: String, reason: Option<String> },
+    /// The authentication process failed.
+    AuthFailed { reason: String },
+    /// No ongoing authentication was started with the given login key.
+    NoOngoingAuth { login_key: String },
+    /// The account has no Minecraft profile.
+    ProfileMissing,
+    /// Reading or writing a data file failed.
+    Io { reason: String },
+}
+
+impl ClientworksError {
+    /// Wraps an [`InstanceEndError`] raised by the instance `key`.
+    pub fn instance_end(key: &Uuid, err: InstanceEndError) -> Self {
+        match err {
+            InstanceEndError::Timeout => Self::InstanceTimeout { key: key.to_string() },
+            _ => Self::InstanceOffline { key: key.to_string() },
+        }
+    }
+}
+
+impl fmt::Display for ClientworksError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ClientworksError::*;
+
+        match self {
+            InvalidId { id } => write!(f, "Invalid UUID: {id}"),
+            ClientNotFound { id } => write!(f, "Client {id} does not exist"),
+            ClientExists { username } => write!(f, "Client {username} already exists"),
+            ServerNotFound { name } => write!(f, "Server '{name}' not found"),
+            ServerExists { name } => write!(f, "Server {name} already exists"),
+            ServerUnreachable { name, reason } => write!(f, "Failed to ping server {name}: {reason}"),
+            InvalidVersion { version } => write!(f, "Unsupported version: {version}"),
+            UnsupportedVersion { name, version, protocol } => {
+                write!(f, "Server {name} runs {version} (protocol {protocol}), which is not supported")
+            }
+            SwarmNotFound { name } => write!(f, "Swarm '{name}' not found"),
+            SwarmExists { name } => write!(f, "Swarm {name} already exists"),
+            MacroNotFound { name } => write!(f, "Macro '{name}' not found"),
+            InvalidMacro { name, reason } => write!(f, "Invalid macro {name}: {reason}"),
+            MacroRunNotFound { run } => write!(f, "No ongoing macro run found from id: {run}"),
+            ScriptNotFound { name } => write!(f, "Script '{name}' not found"),
+            ProxyNotFound { name } => write!(f, "Proxy '{name}' not found"),
+            ProxyExists { name } => write!(f, "Proxy {name} already exists"),
+            InvalidProxy { proxy, reason } => write!(f, "Invalid proxy '{proxy}': {reason}"),
+            ProxyPoolEmpty => write!(f, "The proxy pool is empty"),
+            InvalidCommandPrefix { prefix } => write!(f, "Invalid command prefix: '{prefix}'"),
+            ControllerMissing { id } => write!(f, "No client controller found from id: {id}"),
+            InstanceNotFound { key, .. } => write!(f, "No client instance found from key: {key}"),
+            InstanceOffline { key } => write!(f, "Instance {key} is offline"),
+            InstanceTimeout { key } => write!(f, "Thread cancellation of instance {key} has timed out"),
+            AuthKeyMissing { uuid } => write!(f, "No authentication key found in cache for account {uuid}"),
+            AuthCacheLocked => write!(f, "The authentication cache is locked, unlock it with its passphrase first"),
+            InvalidPassphrase { reason } => write!(f, "Invalid passphrase: {reason}"),
+            AuthExpired { login_key, reason } => match reason {
+                Some(reason) => write!(f, "Authentication of {login_key} has expired, re-authentication is required: {reason}"),
+                None => write!(f, "Account {login_key} not found in cache or cached token(s) have expired"),
+            },
+            AuthFailed { reason } => write!(f, "Authentication failed: {reason}"),
+            NoOngoingAuth { login_key } => write!(f, "No ongoing auth found from provided login key: {login_key}"),
+            ProfileMissing => write!(f, "No profile found from account"),
+            Io { reason } => write!(f, "Failed to access data files: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ClientworksError {}
+
+impl From<ClientworksError> for String {
+    fn from(value: ClientworksError) -> Self {
+        format!("{value}")
+    }
+}
+
+impl From<io::Error> for ClientworksError {
+    fn from(value: io::Error) -> Self {
+        Self::Io { reason: value.to_string() }
+    }
+}
+
+/// Parses a UUID provided to a command.
+pub(crate) fn parse_id(id: &str) -> Result<Uuid, ClientworksError> {
+    id.parse().map_err(|_| ClientworksError::InvalidId { id: id.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::ClientworksError;
+
+    #[test]
+    fn error_serialization() {
+        let err = ClientworksError::InstanceNotFound { id: "a".to_string(), key: "b".to_string() };
+        assert_eq!(
+            serde_json::to_string(&err).unwrap(),
+            r#"{"code":"InstanceNotFound","id":"a","key":"b"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&ClientworksError::ProfileMissing).unwrap(),
+            r#"{"code":"ProfileMissing"}"#
+        );
+    }
+}
@@ -0,0 +1,401 @@
+use log::{error, info, warn};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    io, path::Path,
+    fs,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, LazyLock
+    },
+    time::Duration
+};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+use crate::{
+    AppState,
+    api::{ApiContext, ClientworksError, controller, parse_id},
+    client::{
+        hooks::{chatlog::ChatBuffer, MacroStatus, Payload, Publisher},
+        network::ConnectionState
+    }
+};
+
+/// How long a `Disconnect` or `Reconnect` step waits for the instance to go offline or to
+/// join the game.
+const STATE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Chat lines are stored with their ANSI colours, which patterns should not have to match.
+static ANSI_ESCAPE: LazyLock<Regex> = LazyLock::new(|| Regex::new("\x1b\\[[0-9;]*m").unwrap());
+
+/// A single step of a [`Macro`], e.g. `{ "type": "WaitForChat", "pattern": "^Welcome" }`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum MacroStep {
+    /// Sends a chat message.
+    Chat { message: String },
+    /// Runs a command, given with or without its leading slash.
+    Command { command: String },
+    /// Waits for the given delay, in milliseconds.
+    Wait { ms: u64 },
+    /// Waits for a chat line matching the regular expression `pattern`, received since the
+    /// run started or since the line matched by the previous `WaitForChat` step.
+    /// Fails after `timeout_ms`, if given.
+    WaitForChat {
+        pattern: String,
+        #[serde(default)]
+        timeout_ms: Option<u64>
+    },
+    /// Disconnects the instance and waits until it is offline.
+    Disconnect,
+    /// Connects the instance again, disconnecting it first if needed, and waits until it
+    /// has joined the game.
+    Reconnect,
+}
+
+/// A named sequence of steps run against one or more instances, saved in `macros.json`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Macro {
+    pub name: String,
+    pub steps: Vec<MacroStep>,
+}
+
+impl Macro {
+    /// Makes sure that every chat pattern of the macro compiles.
+    fn validate(&self) -> Result<(), ClientworksError> {
+        for step in &self.steps {
+            if let MacroStep::WaitForChat { pattern, .. } = step {
+                Regex::new(pattern).map_err(|e| ClientworksError::InvalidMacro {
+                    name: self.name.clone(),
+                    reason: e.to_string()
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct List(pub(crate) HashMap<String, Macro>);
+
+impl List {
+    pub(crate) fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    pub(crate) fn from_file(path: &Path) -> Self {
+        let path = path.join("macros.json");
+        if !path.exists() {
+            fs::write(&path, "{}");
+        }
+        let raw = fs::read_to_string(&path);
+        if let Ok(content) = raw {
+            match serde_json::from_str(content.as_str()) {
+                Ok(list) => return list,
+                Err(e) => error!("Failed to parse macro list: {e}"),
+            }
+        }
+        error!("Failed to load macro list from {path:?}");
+        Self::new()
+    }
+
+    pub fn write_to_file(&self, path: &Path) -> io::Result<()> {
+        let path = path.join("macros.json");
+        info!("Writing macro list to {path:?}");
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn get_macro(&self, name: &str) -> Option<&Macro> {
+        self.0.get(name)
+    }
+}
+
+/// An instance a macro is run against.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MacroTarget {
+    /// The ID of the client
+    pub client: String,
+    /// The key of the instance
+    pub instance: String,
+}
+
+/// The task running a macro on a single instance.
+struct RunTask {
+    target: MacroTarget,
+    publisher: Publisher,
+    /// The step being run, counting from 1
+    step: Arc<AtomicUsize>,
+    handle: JoinHandle<()>,
+}
+
+/// An ongoing run of a macro, on any number of instances at once.
+pub struct MacroRun {
+    name: String,
+    steps: usize,
+    tasks: Vec<RunTask>,
+}
+
+impl MacroRun {
+    fn is_finished(&self) -> bool {
+        self.tasks.iter().all(|task| task.handle.is_finished())
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct MacroRunInfo {
+    pub run: String,
+    pub name: String,
+    /// The instances still running the macro
+    pub targets: Vec<MacroTarget>,
+}
+
+fn save(api: &mut ApiContext) -> Result<(), ClientworksError> {
+    match api.macros.write_to_file(&api.save) {
+        Err(e) => {
+            warn!("Failed to write macro list: {e}");
+            Err(e.into())
+        },
+        Ok(_) => Ok(())
+    }
+}
+
+/// Saves a macro, replacing any macro with the same name.
+///
+/// # Errors
+/// * [`ClientworksError::InvalidMacro`] - if a chat pattern isn't a valid regular expression
+pub fn save_macro(state: &AppState, macro_: Macro) -> Result<(), ClientworksError> {
+    macro_.validate()?;
+    let mut ctx = state.api_context.lock().unwrap();
+    info!("Saving macro {} of {} step(s)", macro_.name, macro_.steps.len());
+    ctx.macros.0.insert(macro_.name.clone(), macro_);
+    save(&mut ctx)
+}
+
+/// Deletes a macro. Its ongoing runs are left untouched.
+pub fn delete_macro(state: &AppState, name: String) -> Result<(), ClientworksError> {
+    let mut ctx = state.api_context.lock().unwrap();
+    if ctx.macros.0.remove(&name).is_none() {
+        return Err(ClientworksError::MacroNotFound { name });
+    }
+    info!("Deleting macro {name}");
+    save(&mut ctx)
+}
+
+pub fn get_macros(state: &AppState) -> Vec<Macro> {
+    let ctx = state.api_context.lock().unwrap();
+    ctx.macros.0.values().cloned().collect()
+}
+
+/// Starts running a macro on every target instance at once, in the background.
+///
+/// The progress of each instance is published to the event channel as a [`Payload::Macro`],
+/// on every step and once the run has completed, failed or was cancelled. A step failing,
+/// e.g. a chat message sent while offline, only ends the run on that instance.
+///
+/// # Returns
+/// The ID of the run, to cancel it with [`cancel_macro`]
+///
+/// # Errors
+/// * [`ClientworksError::MacroNotFound`] - if no macro is saved under `name`
+/// * [`ClientworksError::InstanceNotFound`] - if any of the targets doesn't exist
+pub fn start_macro(
+    state: &AppState,
+    name: String,
+    targets: Vec<MacroTarget>
+) -> Result<String, ClientworksError> {
+    let mut ctx = state.api_context.lock().unwrap();
+    let macro_ = ctx.macros.get_macro(&name)
+        .cloned()
+        .ok_or_else(|| ClientworksError::MacroNotFound { name: name.clone() })?;
+    macro_.validate()?;
+
+    let mut chats = Vec::new();
+    for target in &targets {
+        let key = parse_id(&target.instance)?;
+        let instance = controller::locate_instance(&mut ctx, target.client.clone(), &key)?;
+        chats.push((key, instance.chat().clone()));
+    }
+
+    ctx.macro_runs.retain(|_, run| !run.is_finished());
+    let run = Uuid::new_v4();
+    info!("Running macro {name} on {} instance(s) as {run}", targets.len());
+    let tasks = targets.into_iter()
+        .zip(chats)
+        .map(|(target, (key, chat))| {
+            let publisher = Publisher::new(key, ctx.events.clone());
+            let step = Arc::new(AtomicUsize::new(0));
+            let handle = tokio::spawn(run_steps(
+                state.clone(), run, macro_.clone(), target.clone(),
+                chat, publisher.clone(), step.clone()
+            ));
+            RunTask { target, publisher, step, handle }
+        })
+        .collect();
+    ctx.macro_runs.insert(run, MacroRun { name, steps: macro_.steps.len(), tasks });
+    Ok(run.to_string())
+}
+
+/// Cancels a macro run on all of its instances.
+pub fn cancel_macro(state: &AppState, run: String) -> Result<(), ClientworksError> {
+    let id = parse_id(&run)?;
+    let mut ctx = state.api_context.lock().unwrap();
+    let macro_run = ctx.macro_runs.remove(&id)
+        .filter(|macro_run| !macro_run.is_finished())
+        .ok_or(ClientworksError::MacroRunNotFound { run })?;
+    info!("Cancelling macro run {id} of {}", macro_run.name);
+    for task in macro_run.tasks.into_iter().filter(|task| !task.handle.is_finished()) {
+        task.handle.abort();
+        task.publisher.try_publish(Payload::Macro {
+            run: id,
+            name: macro_run.name.clone(),
+            step: task.step.load(Ordering::Relaxed),
+            steps: macro_run.steps,
+            status: MacroStatus::Cancelled
+        });
+    }
+    Ok(())
+}
+
+/// The macro runs that haven't finished on all of their instances yet.
+pub fn get_macro_runs(state: &AppState) -> Vec<MacroRunInfo> {
+    let mut ctx = state.api_context.lock().unwrap();
+    ctx.macro_runs.retain(|_, run| !run.is_finished());
+    ctx.macro_runs.iter()
+        .map(|(run, macro_run)| MacroRunInfo {
+            run: run.to_string(),
+            name: macro_run.name.clone(),
+            targets: macro_run.tasks.iter()
+                .filter(|task| !task.handle.is_finished())
+                .map(|task| task.target.clone())
+                .collect()
+        })
+        .collect()
+}
+
+async fn run_steps(
+    state: AppState,
+    run: Uuid,
+    macro_: Macro,
+    target: MacroTarget,
+    chat: ChatBuffer,
+    publisher: Publisher,
+    current: Arc<AtomicUsize>
+) {
+    let steps = macro_.steps.len();
+    let progress = |step, status| Payload::Macro { run, name: macro_.name.clone(), step, steps, status };
+    let mut cursor = chat.last_seq();
+    for (index, step) in macro_.steps.iter().enumerate() {
+        current.store(index + 1, Ordering::Relaxed);
+        publisher.publish(progress(index + 1, MacroStatus::Running)).await;
+        if let Err(reason) = execute(&state, &target, &chat, &mut cursor, step).await {
+            warn!("Macro {} failed on instance {} at step {}: {reason}", macro_.name, target.instance, index + 1);
+            publisher.publish(progress(index + 1, MacroStatus::Failed { reason })).await;
+            return
+        }
+    }
+    publisher.publish(progress(steps, MacroStatus::Completed)).await;
+}
+
+async fn execute(
+    state: &AppState,
+    target: &MacroTarget,
+    chat: &ChatBuffer,
+    cursor: &mut u64,
+    step: &MacroStep
+) -> Result<(), String> {
+    let (id, key) = (target.client.clone(), target.instance.clone());
+    match step {
+        MacroStep::Chat { message } => controller::send_chat(state, id, key, message.clone())?,
+        MacroStep::Command { command } => {
+            controller::send_chat(state, id, key, format!("/{}", command.trim_start_matches('/')))?
+        }
+        MacroStep::Wait { ms } => tokio::time::sleep(Duration::from_millis(*ms)).await,
+        MacroStep::WaitForChat { pattern, timeout_ms } => {
+            let regex = Regex::new(pattern).map_err(|e| e.to_string())?;
+            let matched = async {
+                loop {
+                    for line in chat.wait_since(*cursor).await {
+                        *cursor = line.seq;
+                        if regex.is_match(&ANSI_ESCAPE.replace_all(&line.message, "")) {
+                            return
+                        }
+                    }
+                }
+            };
+            match timeout_ms {
+                Some(ms) => tokio::time::timeout(Duration::from_millis(*ms), matched).await
+                    .map_err(|_| format!("No chat line matched '{pattern}' within {ms}ms"))?,
+                None => matched.await
+            }
+        }
+        MacroStep::Disconnect => {
+            controller::disconnect_client(state, id, key).await?;
+            wait_state(state, target, |current| !current.is_running()).await?;
+        }
+        MacroStep::Reconnect => {
+            if instance_state(state, target)?.is_running() {
+                controller::disconnect_client(state, id.clone(), key.clone()).await?;
+                wait_state(state, target, |current| !current.is_running()).await?;
+            }
+            controller::connect_client(state, id, key)?;
+            let joined = wait_state(state, target, |current| !current.is_joining()).await?;
+            if joined != ConnectionState::Playing {
+                return Err(format!("Failed to join the server: {joined}"))
+            }
+        }
+    }
+    Ok(())
+}
+
+fn instance_state(state: &AppState, target: &MacroTarget) -> Result<ConnectionState, ClientworksError> {
+    let key = parse_id(&target.instance)?;
+    let mut ctx = state.api_context.lock().unwrap();
+    Ok(controller::locate_instance(&mut ctx, target.client.clone(), &key)?.state())
+}
+
+/// Polls the state of the instance until `condition` holds, for up to [`STATE_TIMEOUT`].
+async fn wait_state(
+    state: &AppState,
+    target: &MacroTarget,
+    condition: impl Fn(&ConnectionState) -> bool
+) -> Result<ConnectionState, String> {
+    let poll = async {
+        loop {
+            let current = instance_state(state, target)?;
+            if condition(&current) {
+                return Ok::<_, String>(current)
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+    };
+    tokio::time::timeout(STATE_TIMEOUT, poll).await
+        .map_err(|_| format!("Timed out after {}s", STATE_TIMEOUT.as_secs()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::{macros::{Macro, MacroStep}, ClientworksError};
+
+    #[test]
+    fn macro_format() {
+        let macro_: Macro = serde_json::from_str(r#"{
+            "name": "login",
+            "steps": [
+                { "type": "WaitForChat", "pattern": "^Please log ?in", "timeout_ms": 5000 },
+                { "type": "Command", "command": "login hunter2" },
+                { "type": "Wait", "ms": 500 },
+                { "type": "Reconnect" }
+            ]
+        }"#).unwrap();
+        assert_eq!(macro_.steps[1], MacroStep::Command { command: "login hunter2".to_string() });
+        assert_eq!(macro_.steps[3], MacroStep::Reconnect);
+        assert!(macro_.validate().is_ok());
+
+        let invalid = Macro {
+            name: "broken".to_string(),
+            steps: vec![MacroStep::WaitForChat { pattern: "(".to_string(), timeout_ms: None }]
+        };
+        assert!(matches!(invalid.validate(), Err(ClientworksError::InvalidMacro { .. })));
+    }
+}
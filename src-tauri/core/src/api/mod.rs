@@ -0,0 +1,291 @@
+#![allow(unused)]
+
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Mutex,
+    str::FromStr,
+    ops::DerefMut
+};
+use tokio::fs;
+use uuid::Uuid;
+
+pub mod auth;
+mod client;
+pub mod controller;
+mod error;
+pub mod macros;
+pub mod proxy;
+mod server;
+pub mod swarm;
+mod vault;
+
+pub use server::{
+    List as ServerList, Server,
+    CachedStatus, STATUS_TTL
+};
+pub use client::{
+    List as ClientList, Client,
+    ClientConnection, AuthType
+};
+pub use swarm::{
+    List as SwarmList, Swarm
+};
+pub use proxy::{
+    List as ProxyList, ProxyInfo
+};
+pub use macros::{
+    List as MacroList, Macro, MacroStep,
+    MacroRun, MacroRunInfo, MacroTarget
+};
+pub use error::ClientworksError;
+pub(crate) use error::parse_id;
+
+use crate::{
+    api::{
+        auth::AuthCache,
+        client::AuthType::Offline
+    },
+    client::{
+        ClientController,
+        ControllerContainer,
+        auth::{Authentication, Endpoints},
+        hooks::EventSender
+    },
+    AppState
+};
+use crate::client::Version;
+
+// The functions in this module and its submodules make up the command layer of Clientworks.
+// They only depend on the shared [`AppState`], so they can be driven by the Tauri app,
+// the HTTP API and the headless CLI alike.
+
+pub struct ApiContext {
+    pub controllers: ControllerContainer,
+    pub clients: ClientList,
+    pub servers: ServerList,
+    pub save: PathBuf,
+    pub ongoing_auths: HashMap<String, Authentication>,
+    /// The HTTP client Microsoft and Yggdrasil accounts authenticate and refresh their
+    /// tokens with
+    pub http_client: reqwest::Client,
+    /// The services Microsoft accounts authenticate and refresh their tokens with, the real
+    /// ones unless replaced, e.g. by a stand-in
+    pub ms_endpoints: Endpoints,
+    pub auth_cache: AuthCache,
+    /// The login keys of the cached accounts whose tokens could not be refreshed, see
+    /// [`auth::refresh_expiring_tokens`]
+    pub reauth_required: HashSet<String>,
+    pub swarms: SwarmList,
+    /// The background tasks connecting the members of launched swarms, by swarm name
+    pub swarm_launches: HashMap<String, tokio::task::JoinHandle<()>>,
+    pub macros: MacroList,
+    /// The ongoing macro runs, by run ID
+    pub macro_runs: HashMap<Uuid, MacroRun>,
+    /// The last status query of every saved server, by server name
+    pub server_statuses: HashMap<String, CachedStatus>,
+    pub proxies: ProxyList,
+    /// How many proxies were drawn from the proxy pool, see [`proxy::POOL`]
+    pub proxy_cursor: usize,
+    /// The event channel handed to every controller, and in turn to their instances
+    pub events: EventSender
+}
+
+pub fn load_from_dir(path: PathBuf, events: EventSender) -> ApiContext {
+    info!("Initialised app data from directory: {path:?}");
+    // before the client list and swarms, which refer to versions
+    crate::client::version::load_overrides(&path);
+    if let Err(e) = std::fs::create_dir_all(path.join(crate::client::scripting::SCRIPTS_DIR)) {
+        error!("Failed to create scripts directory: {e}");
+    }
+    ApiContext {
+        controllers: ControllerContainer::new(),
+        events,
+        clients: ClientList::from_file(&path),
+        servers: ServerList::from_file(&path),
+        auth_cache: AuthCache::from_file(&path),
+        reauth_required: HashSet::new(),
+        swarms: SwarmList::from_file(&path),
+        swarm_launches: HashMap::new(),
+        macros: MacroList::from_file(&path),
+        macro_runs: HashMap::new(),
+        server_statuses: HashMap::new(),
+        proxies: ProxyList::from_file(&path),
+        proxy_cursor: 0,
+        save: path,
+        ongoing_auths: HashMap::new(),
+        http_client: reqwest::Client::new(),
+        ms_endpoints: Endpoints::default()
+    }
+}
+
+impl ApiContext {
+    /// The directory holding the logs of every instance, see [`crate::client::hooks::logs`].
+    pub fn logs_dir(&self) -> PathBuf {
+        self.save.join(crate::client::hooks::logs::LOGS_DIR)
+    }
+
+    /// The directory holding the scripts instances may run, see [`crate::client::scripting`].
+    pub fn scripts_dir(&self) -> PathBuf {
+        self.save.join(crate::client::scripting::SCRIPTS_DIR)
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ClientInfo {
+    pub id: String,
+    pub username: String,
+    pub auth: bool,
+    pub auth_type: AuthType,
+    pub uuid: String,
+    pub instance_count: usize,
+    /// Whether the client is an authenticated account that can't authenticate until the
+    /// encrypted authentication cache is unlocked
+    pub locked: bool
+}
+
+pub fn remove_client(state: &AppState, uuid: String) -> Result<(), ClientworksError> {
+    let mut ctx = state.api_context.lock().unwrap();
+    client::unregister(&mut ctx, uuid)
+}
+
+fn map_client_info(client: &mut Client, cache_locked: bool) -> ClientInfo {
+    ClientInfo {
+        id: client.id.to_string(),
+        username: client.username.clone(),
+        auth: client.auth != Offline,
+        auth_type: client.auth.clone(),
+        uuid: client.uuid.to_string(),
+        instance_count: client.connections.len(),
+        locked: client.auth != Offline && cache_locked
+    }
+}
+
+pub fn get_client(state: &AppState, id: String) -> Option<ClientInfo> {
+    let ctx = state.api_context.lock().unwrap();
+    ctx.clients
+        .0
+        .values()
+        .find(|client| client.id.to_string() == id)
+        .cloned()
+        .map(|mut client| map_client_info(&mut client, ctx.auth_cache.is_locked()))
+}
+
+pub fn get_client_by_user(
+    state: &AppState,
+    username: String,
+) -> Option<ClientInfo> {
+    let ctx = state.api_context.lock().unwrap();
+    ctx.clients
+        .get_by_username(&username)
+        .cloned()
+        .map(|mut client| map_client_info(&mut client, ctx.auth_cache.is_locked()))
+}
+
+pub fn get_clients(state: &AppState) -> Vec<ClientInfo> {
+    let ctx = state.api_context.lock().unwrap();
+    ctx.clients
+        .0
+        .values()
+        .cloned()
+        .map(|mut client| map_client_info(&mut client, ctx.auth_cache.is_locked()))
+        .collect()
+}
+
+#[derive(Serialize, Debug)]
+pub struct ServerInfo {
+    pub name: String,
+    pub ip: String,
+    pub port: u16,
+    pub connections: u32,
+    /// The last cached status of the server, see [`get_server_status`]
+    pub status: Option<CachedStatus>,
+}
+
+pub fn add_server(
+    state: &AppState,
+    name: String,
+    ip: String,
+    port: u16,
+) -> Result<(), ClientworksError> {
+    let mut ctx = state.api_context.lock().unwrap();
+    server::create(&mut ctx, name, ip, port)
+}
+
+pub fn delete_server(state: &AppState, name: String) -> Result<(), ClientworksError> {
+    let mut ctx = state.api_context.lock().unwrap();
+    server::delete(&mut ctx, name)
+}
+
+pub fn get_servers(state: &AppState) -> Vec<ServerInfo> {
+    let ctx = state.api_context.lock().unwrap();
+    ctx.servers
+        .0
+        .values()
+        .cloned()
+        .map(|server| ServerInfo {
+            status: ctx.server_statuses.get(&server.name).cloned(),
+            name: server.name,
+            ip: server.ip,
+            port: server.port,
+            connections: 0,
+        })
+        .collect()
+}
+
+/// The status of a saved server: MOTD, players, version, favicon and latency, queried with a
+/// Server List Ping. The cached status is returned unless `refresh` is set or it is older
+/// than [`STATUS_TTL`].
+///
+/// A failed query isn't an error, see [`CachedStatus::error`].
+pub async fn get_server_status(
+    state: &AppState,
+    name: String,
+    refresh: bool
+) -> Result<CachedStatus, ClientworksError> {
+    let server = {
+        let ctx = state.api_context.lock().unwrap();
+        let cached = ctx.server_statuses.get(&name).filter(|cached| !refresh && !cached.is_stale());
+        if let Some(cached) = cached {
+            return Ok(cached.clone())
+        }
+        ctx.servers.get_server(&name)
+            .cloned()
+            .ok_or_else(|| ClientworksError::ServerNotFound { name: name.clone() })?
+    };
+    let status = server::query_status(&server).await;
+    cache_status(state, status.clone());
+    Ok(status)
+}
+
+/// The statuses of all saved servers, see [`get_server_status`]. Servers are queried
+/// concurrently.
+pub async fn get_server_statuses(state: &AppState, refresh: bool) -> Vec<CachedStatus> {
+    let names: Vec<String> = state.api_context.lock().unwrap().servers.0.keys().cloned().collect();
+    let statuses = futures::future::join_all(
+        names.into_iter().map(|name| get_server_status(state, name, refresh))
+    ).await;
+    // servers deleted in the meantime are left out
+    let mut statuses: Vec<_> = statuses.into_iter().flatten().collect();
+    statuses.sort_by(|a, b| a.server.cmp(&b.server));
+    statuses
+}
+
+/// Queries the status of every saved server every `interval`, forever. Meant to be spawned
+/// once by long-running frontends.
+pub async fn refresh_server_statuses(state: AppState, interval: std::time::Duration) {
+    loop {
+        get_server_statuses(&state, true).await;
+        tokio::time::sleep(interval).await;
+    }
+}
+
+fn cache_status(state: &AppState, status: CachedStatus) {
+    let mut ctx = state.api_context.lock().unwrap();
+    // the server may have been deleted while it was being queried
+    if ctx.servers.0.contains_key(&status.server) {
+        ctx.server_statuses.insert(status.server.clone(), status);
+    }
+}
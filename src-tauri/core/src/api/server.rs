@@ -1,4 +1,7 @@
-use crate::api::ApiContext;
+use crate::{
+    api::{ApiContext, ClientworksError},
+    client::ping::{self, ServerStatus}
+};
 use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
 use std::{
@@ -7,22 +10,23 @@ use std::{
     fs::{
         self, File
     },
-    fmt::Display
+    fmt::Display,
+    time::Duration
 };
 
-fn save(api: &mut ApiContext) -> Result<(), String> {
+fn save(api: &mut ApiContext) -> Result<(), ClientworksError> {
     match api.servers.write_to_file(&api.save) {
         Err(e) => {
             warn!("Failed to write server list: {e}");
-            Err(e.to_string())
+            Err(e.into())
         },
         Ok(_) => Ok(())
     }
 }
 
-pub fn create(api: &mut ApiContext, name: String, ip: String, port: u16) -> Result<(), String> {
+pub fn create(api: &mut ApiContext, name: String, ip: String, port: u16) -> Result<(), ClientworksError> {
     if api.servers.0.contains_key(&name) {
-        return Err(format!("Server {name} already exists"));
+        return Err(ClientworksError::ServerExists { name });
     }
     info!("Creating server {ip}:{port} as {name}");
     api.servers
@@ -31,15 +35,51 @@ pub fn create(api: &mut ApiContext, name: String, ip: String, port: u16) -> Resu
     save(api)
 }
 
-pub fn delete(api: &mut ApiContext, name: String) -> Result<(), String> {
+pub fn delete(api: &mut ApiContext, name: String) -> Result<(), ClientworksError> {
     if !api.servers.0.contains_key(&name) {
-        return Err(format!("Server {name} does not exist"));
+        return Err(ClientworksError::ServerNotFound { name });
     }
     info!("Deleting server {name}");
     api.servers.0.remove(&name);
+    api.server_statuses.remove(&name);
     save(api)
 }
 
+/// How long a server status is cached before being queried again.
+pub const STATUS_TTL: Duration = Duration::from_secs(60);
+
+/// The last status query of a saved server, successful or not.
+#[derive(Serialize, Clone, Debug)]
+pub struct CachedStatus {
+    /// The name of the server
+    pub server: String,
+    /// When the server was queried, in milliseconds since the Unix epoch
+    pub updated: i64,
+    pub status: Option<ServerStatus>,
+    /// Why the query failed, e.g. because the server is offline
+    pub error: Option<String>,
+}
+
+impl CachedStatus {
+    pub fn is_stale(&self) -> bool {
+        chrono::Utc::now().timestamp_millis() - self.updated >= STATUS_TTL.as_millis() as i64
+    }
+}
+
+/// Queries the status of a server, without touching the cache.
+pub(crate) async fn query_status(server: &Server) -> CachedStatus {
+    let result = ping::ping(server).await;
+    if let Err(e) = &result {
+        info!("Status query of server {} failed: {e}", server.name);
+    }
+    CachedStatus {
+        server: server.name.clone(),
+        updated: chrono::Utc::now().timestamp_millis(),
+        error: result.as_ref().err().cloned(),
+        status: result.ok()
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct List(pub(crate) HashMap<String, Server>);
 
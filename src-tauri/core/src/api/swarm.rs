@@ -0,0 +1,327 @@
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    io, path::Path,
+    fs,
+    time::Duration
+};
+use uuid::Uuid;
+use crate::{
+    AppState,
+    api::{
+        self, ApiContext, ClientworksError,
+        client::AuthType,
+        controller,
+        parse_id,
+        proxy
+    },
+    client::{
+        auth::AuthState,
+        Version,
+        AUTO_VERSION
+    }
+};
+
+/// A group of clients sent to the same server, saved in `swarms.json`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Swarm {
+    pub name: String,
+    /// The IDs of the member clients, in joining order
+    pub clients: Vec<Uuid>,
+    /// The name of the target server, see [`crate::api::ServerList`]
+    pub server: String,
+    /// The name of the version of the members, or [`AUTO_VERSION`] to detect it on every launch
+    pub version: String,
+    /// The delay between two clients starting to connect, in milliseconds
+    pub join_delay_ms: u64,
+    /// How many clients may be connecting at the same time
+    pub concurrency: usize,
+    /// The instance created for each member client, by client ID, reused on every launch
+    #[serde(default)]
+    pub instances: HashMap<Uuid, Uuid>,
+    /// The proxy of the instances created for the members, see [`proxy::resolve`]. With
+    /// [`proxy::POOL`], each instance draws its own proxy from the pool.
+    #[serde(default)]
+    pub proxy: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct List(pub(crate) HashMap<String, Swarm>);
+
+impl List {
+    pub(crate) fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    pub(crate) fn from_file(path: &Path) -> Self {
+        let path = path.join("swarms.json");
+        if !path.exists() {
+            fs::write(&path, "{}");
+        }
+        let raw = fs::read_to_string(&path);
+        if let Ok(content) = raw {
+            match serde_json::from_str(content.as_str()) {
+                Ok(list) => return list,
+                Err(e) => error!("Failed to parse swarm list: {e}"),
+            }
+        }
+        error!("Failed to load swarm list from {path:?}");
+        Self::new()
+    }
+
+    pub fn write_to_file(&self, path: &Path) -> io::Result<()> {
+        let path = path.join("swarms.json");
+        info!("Writing swarm list to {path:?}");
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn get_swarm(&self, name: &str) -> Option<&Swarm> {
+        self.0.get(name)
+    }
+}
+
+fn save(api: &mut ApiContext) -> Result<(), ClientworksError> {
+    match api.swarms.write_to_file(&api.save) {
+        Err(e) => {
+            warn!("Failed to write swarm list: {e}");
+            Err(e.into())
+        },
+        Ok(_) => Ok(())
+    }
+}
+
+fn find(api: &ApiContext, name: &str) -> Result<Swarm, ClientworksError> {
+    api.swarms.get_swarm(name)
+        .cloned()
+        .ok_or_else(|| ClientworksError::SwarmNotFound { name: name.to_string() })
+}
+
+/// Saves a new swarm of registered clients, sent to a saved server.
+///
+/// # Parameters
+/// * `clients` - the IDs of the member clients, in joining order
+/// * `join_delay_ms` - the delay between two clients starting to connect
+/// * `concurrency` - how many clients may be connecting at the same time, at least 1
+///
+/// # Errors
+/// * [`ClientworksError::SwarmExists`] - if a swarm with the same name already exists
+/// * [`ClientworksError::ClientNotFound`] - if any of the clients isn't registered
+/// * [`ClientworksError::ServerNotFound`] - if the server isn't saved
+/// * [`ClientworksError::InvalidVersion`] - if the version is neither supported nor
+///   [`AUTO_VERSION`]
+pub fn create_swarm(
+    state: &AppState,
+    name: String,
+    clients: Vec<String>,
+    server_name: String,
+    version: String,
+    join_delay_ms: u64,
+    concurrency: usize
+) -> Result<(), ClientworksError> {
+    let mut ctx = state.api_context.lock().unwrap();
+    if ctx.swarms.0.contains_key(&name) {
+        return Err(ClientworksError::SwarmExists { name });
+    }
+    if ctx.servers.get_server(&server_name).is_none() {
+        return Err(ClientworksError::ServerNotFound { name: server_name });
+    }
+    if !version.eq_ignore_ascii_case(AUTO_VERSION) && Version::from_string(&version).is_none() {
+        return Err(ClientworksError::InvalidVersion { version });
+    }
+    let clients = clients.iter()
+        .map(|id| {
+            let uuid = parse_id(id)?;
+            ctx.clients.get_by_id(&uuid)
+                .map(|client| client.id)
+                .ok_or_else(|| ClientworksError::ClientNotFound { id: id.clone() })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    info!("Creating swarm {name} of {} client(s) on {server_name}", clients.len());
+    ctx.swarms.0.insert(name.clone(), Swarm {
+        name,
+        clients,
+        server: server_name,
+        version,
+        join_delay_ms,
+        concurrency: concurrency.max(1),
+        instances: HashMap::new(),
+        proxy: None
+    });
+    save(&mut ctx)
+}
+
+/// Sets the proxy of the instances created for the members of a swarm from its next launch,
+/// or removes it if `proxy` is `None`. Instances created by previous launches are left as is.
+///
+/// # Errors
+/// * [`ClientworksError::SwarmNotFound`] - if the swarm isn't saved
+/// * [`ClientworksError::ProxyNotFound`] / [`ClientworksError::InvalidProxy`] - if the proxy
+///   isn't [`proxy::POOL`] and can't be resolved
+pub fn set_swarm_proxy(state: &AppState, name: String, proxy: Option<String>) -> Result<(), ClientworksError> {
+    let mut ctx = state.api_context.lock().unwrap();
+    if !ctx.swarms.0.contains_key(&name) {
+        return Err(ClientworksError::SwarmNotFound { name });
+    }
+    // the pool is only drawn from at launch, as it may be filled in the meantime
+    if let Some(spec) = proxy.as_ref().filter(|spec| *spec != proxy::POOL) {
+        proxy::resolve(&mut ctx, spec)?;
+    }
+    if let Some(swarm) = ctx.swarms.0.get_mut(&name) {
+        swarm.proxy = proxy;
+    }
+    save(&mut ctx)
+}
+
+/// Deletes a swarm. Its instances are left untouched.
+pub fn delete_swarm(state: &AppState, name: String) -> Result<(), ClientworksError> {
+    let mut ctx = state.api_context.lock().unwrap();
+    if ctx.swarms.0.remove(&name).is_none() {
+        return Err(ClientworksError::SwarmNotFound { name });
+    }
+    info!("Deleting swarm {name}");
+    if let Some(launch) = ctx.swarm_launches.remove(&name) {
+        launch.abort();
+    }
+    save(&mut ctx)
+}
+
+pub fn get_swarms(state: &AppState) -> Vec<Swarm> {
+    let ctx = state.api_context.lock().unwrap();
+    ctx.swarms.0.values().cloned().collect()
+}
+
+/// Makes sure a member client has a controller, authenticating it from the authentication
+/// cache unless it is an offline account.
+async fn prepare_member(state: &AppState, id: &Uuid) -> Result<(), ClientworksError> {
+    let auth = {
+        let ctx = state.api_context.lock().unwrap();
+        ctx.clients.get_by_id(id)
+            .map(|client| client.auth.clone())
+            .ok_or_else(|| ClientworksError::ClientNotFound { id: id.to_string() })?
+    };
+    if auth != AuthType::Offline {
+        api::auth::recall_authentication(state, id.to_string(), |progress: &AuthState| {
+            debug!("{progress}")
+        }).await?;
+        Ok(())
+    } else {
+        let mut ctx = state.api_context.lock().unwrap();
+        controller::ensure_controller(&mut ctx, id)
+    }
+}
+
+/// Creates the missing instances of a swarm, then connects all of its members in the
+/// background, following its join delay and concurrency limit.
+///
+/// Members that can't be prepared, e.g. Microsoft accounts whose tokens have expired, or whose
+/// instance can't be created are skipped with a warning.
+///
+/// # Returns
+/// The keys of the instances being connected
+pub async fn launch_swarm(state: &AppState, name: String) -> Result<Vec<String>, ClientworksError> {
+    let swarm = find(&state.api_context.lock().unwrap(), &name)?;
+    let mut members = Vec::new();
+    for client in &swarm.clients {
+        if let Err(e) = prepare_member(state, client).await {
+            warn!("Skipping client {client} of swarm {name}: {e}");
+            continue
+        }
+        let existing = swarm.instances.get(client).filter(|key| {
+            let ctx = state.api_context.lock().unwrap();
+            ctx.controllers.get(client).is_some_and(|controller| controller.get_instance(key).is_some())
+        });
+        let key = match existing {
+            Some(key) => *key,
+            None => {
+                let created = controller::create_connection(
+                    state, client.to_string(), swarm.server.clone(), swarm.version.clone(),
+                    swarm.proxy.clone()
+                ).await;
+                match created.and_then(|key| parse_id(&key)) {
+                    Ok(key) => key,
+                    Err(e) => {
+                        warn!("Skipping client {client} of swarm {name}: {e}");
+                        continue
+                    }
+                }
+            }
+        };
+        members.push((*client, key));
+    }
+
+    {
+        let mut ctx = state.api_context.lock().unwrap();
+        if let Some(saved) = ctx.swarms.0.get_mut(&name) {
+            saved.instances.extend(members.iter().copied());
+        }
+        save(&mut ctx)?;
+    }
+
+    info!("Launching swarm {name} with {} member(s)", members.len());
+    let keys = members.iter().map(|(_, key)| key.to_string()).collect();
+    let launch = tokio::spawn(join_members(
+        state.clone(), members,
+        Duration::from_millis(swarm.join_delay_ms), swarm.concurrency
+    ));
+    let mut ctx = state.api_context.lock().unwrap();
+    if let Some(previous) = ctx.swarm_launches.insert(name, launch) {
+        previous.abort();
+    }
+    Ok(keys)
+}
+
+/// Connects the members one by one, waiting `delay` between each, and waiting for a slot
+/// whenever `concurrency` members are already connecting.
+async fn join_members(state: AppState, members: Vec<(Uuid, Uuid)>, delay: Duration, concurrency: usize) {
+    let joining = |state: &AppState| {
+        let ctx = state.api_context.lock().unwrap();
+        members.iter()
+            .filter_map(|(client, key)| ctx.controllers.get(client)?.get_instance(key))
+            .filter(|instance| instance.state().is_joining())
+            .count()
+    };
+    for (index, (client, key)) in members.iter().enumerate() {
+        if index > 0 {
+            tokio::time::sleep(delay).await;
+        }
+        while joining(&state) >= concurrency {
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+        if let Err(e) = controller::connect_client(&state, client.to_string(), key.to_string()) {
+            warn!("Failed to connect instance {key} of swarm member {client}: {e}");
+        }
+    }
+}
+
+/// Applies `action` to every instance of a swarm, after cancelling any ongoing launch.
+async fn for_each_member<F, Fut>(state: &AppState, name: String, action: F) -> Result<(), ClientworksError>
+where
+    F: Fn(String, String) -> Fut,
+    Fut: Future<Output = Result<(), ClientworksError>>
+{
+    let swarm = {
+        let mut ctx = state.api_context.lock().unwrap();
+        if let Some(launch) = ctx.swarm_launches.remove(&name) {
+            launch.abort();
+        }
+        find(&ctx, &name)?
+    };
+    for (client, key) in swarm.instances {
+        match action(client.to_string(), key.to_string()).await {
+            Ok(_) | Err(ClientworksError::InstanceOffline { .. }) => {}
+            Err(e) => warn!("Failed to stop instance {key} of swarm {name}: {e}")
+        }
+    }
+    Ok(())
+}
+
+/// Disconnects every member of a swarm, cancelling its launch if still ongoing.
+pub async fn disconnect_swarm(state: &AppState, name: String) -> Result<(), ClientworksError> {
+    for_each_member(state, name, |id, key| controller::disconnect_client(state, id, key)).await
+}
+
+/// Kills every member of a swarm, cancelling its launch if still ongoing.
+pub async fn kill_swarm(state: &AppState, name: String) -> Result<(), ClientworksError> {
+    for_each_member(state, name, |id, key| controller::kill_client(state, id, key)).await
+}
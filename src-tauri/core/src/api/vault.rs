@@ -0,0 +1,130 @@
+use argon2::Argon2;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, rand_core::RngCore},
+    ChaCha20Poly1305, Key, Nonce
+};
+use serde::{Deserialize, Serialize};
+use crate::api::ClientworksError;
+
+/// The version of the encrypted format, bumped whenever the cipher or the key derivation changes.
+const FORMAT_VERSION: u8 = 1;
+
+const SALT_LEN: usize = 16;
+
+const NONCE_LEN: usize = 12;
+
+/// Data encrypted with ChaCha20-Poly1305, under a key derived from a passphrase with Argon2id.
+/// Binary fields are encoded in base64.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Envelope {
+    pub version: u8,
+    pub salt: String,
+    pub nonce: String,
+    pub data: String,
+}
+
+fn invalid(reason: &str) -> ClientworksError {
+    ClientworksError::InvalidPassphrase { reason: reason.to_string() }
+}
+
+fn corrupted(reason: String) -> ClientworksError {
+    ClientworksError::Io { reason: format!("Encrypted data is corrupted: {reason}") }
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, ClientworksError> {
+    STANDARD.decode(value).map_err(|e| corrupted(e.to_string()))
+}
+
+/// The key encrypting the data of an [`Envelope`]. A new nonce is drawn on every write.
+pub struct Vault {
+    key: [u8; 32],
+    salt: [u8; SALT_LEN],
+}
+
+impl Vault {
+    fn derive(passphrase: &str, salt: [u8; SALT_LEN]) -> Result<Self, ClientworksError> {
+        if passphrase.is_empty() {
+            return Err(invalid("The passphrase can't be empty"))
+        }
+        let mut key = [0; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| invalid(&e.to_string()))?;
+        Ok(Self { key, salt })
+    }
+
+    /// A vault with a new random salt.
+    pub fn new(passphrase: &str) -> Result<Self, ClientworksError> {
+        let mut salt = [0; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self::derive(passphrase, salt)
+    }
+
+    /// Decrypts an envelope, returning its data along with the vault to encrypt it again.
+    ///
+    /// # Errors
+    /// * [`ClientworksError::InvalidPassphrase`] - if the passphrase is wrong
+    /// * [`ClientworksError::Io`] - if the envelope is malformed
+    pub fn open(passphrase: &str, envelope: &Envelope) -> Result<(Self, Vec<u8>), ClientworksError> {
+        if envelope.version != FORMAT_VERSION {
+            return Err(corrupted(format!("unsupported format version {}", envelope.version)))
+        }
+        let salt = decode(&envelope.salt)?.try_into()
+            .map_err(|_| corrupted("invalid salt".to_string()))?;
+        let nonce = decode(&envelope.nonce)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(corrupted("invalid nonce".to_string()))
+        }
+        let vault = Self::derive(passphrase, salt)?;
+        let data = vault.cipher()
+            .decrypt(Nonce::from_slice(&nonce), decode(&envelope.data)?.as_slice())
+            // the tag can't tell a wrong key from tampered data
+            .map_err(|_| invalid("Wrong passphrase"))?;
+        Ok((vault, data))
+    }
+
+    /// Whether the passphrase is the one the vault was derived from.
+    pub fn verify(&self, passphrase: &str) -> bool {
+        Self::derive(passphrase, self.salt).is_ok_and(|vault| vault.key == self.key)
+    }
+
+    pub fn seal(&self, data: &[u8]) -> Result<Envelope, ClientworksError> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let data = self.cipher()
+            .encrypt(&nonce, data)
+            .map_err(|e| ClientworksError::Io { reason: format!("Failed to encrypt data: {e}") })?;
+        Ok(Envelope {
+            version: FORMAT_VERSION,
+            salt: STANDARD.encode(self.salt),
+            nonce: STANDARD.encode(nonce),
+            data: STANDARD.encode(data)
+        })
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&self.key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::{vault::Vault, ClientworksError};
+
+    #[test]
+    fn vault_round_trip() {
+        let vault = Vault::new("correct horse").unwrap();
+        let envelope = vault.seal(br#"{"key":"token"}"#).unwrap();
+        assert!(!envelope.data.contains("token"));
+        // nonces are never reused
+        assert_ne!(vault.seal(b"").unwrap().nonce, envelope.nonce);
+
+        let (opened, data) = Vault::open("correct horse", &envelope).unwrap();
+        assert_eq!(data, br#"{"key":"token"}"#);
+        assert!(opened.verify("correct horse"));
+        assert!(!opened.verify("battery staple"));
+
+        assert!(matches!(Vault::open("battery staple", &envelope), Err(ClientworksError::InvalidPassphrase { .. })));
+        assert!(Vault::new("").is_err());
+    }
+}
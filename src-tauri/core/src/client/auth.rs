@@ -0,0 +1,681 @@
+use azalea_auth::{AccessTokenResponse, cache::ExpiringValue, ProfileResponse};
+use std::{
+    fmt::Display,
+    time::{Duration, SystemTime, UNIX_EPOCH}
+};
+use log::{debug, info};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+/// The client ID of Minecraft for Nintendo Switch, which is allowed to use the device code
+/// flow; the same one azalea authenticates with.
+const CLIENT_ID: &str = "00000000441cc96b";
+
+const SCOPE: &str = "service::user.auth.xboxlive.com::MBI_SSL";
+
+/// Polls for the MSA token are never closer than this, whatever interval the server asks for.
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The base URLs of the services the Microsoft authentication goes through. The defaults are
+/// the real services; tests point every one of them to a local stand-in, see
+/// [`Endpoints::with_base`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Endpoints {
+    /// Serves `/oauth20_connect.srf` and `/oauth20_token.srf`, for the MSA token
+    pub live: String,
+    /// Serves `/user/authenticate`, for the Xbox Live token
+    pub xbox_live: String,
+    /// Serves `/xsts/authorize`, for the XSTS token
+    pub xsts: String,
+    /// Serves `/authentication/login_with_xbox` and `/minecraft/profile`
+    pub minecraft_services: String,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            live: "https://login.live.com".to_string(),
+            xbox_live: "https://user.auth.xboxlive.com".to_string(),
+            xsts: "https://xsts.auth.xboxlive.com".to_string(),
+            minecraft_services: "https://api.minecraftservices.com".to_string()
+        }
+    }
+}
+
+impl Endpoints {
+    /// Every service served from the same base URL, as none of their paths overlap.
+    pub fn with_base(base: &str) -> Self {
+        let base = base.trim_end_matches('/').to_string();
+        Self {
+            live: base.clone(),
+            xbox_live: base.clone(),
+            xsts: base.clone(),
+            minecraft_services: base
+        }
+    }
+
+    fn endpoint(base: &str, path: &str) -> String {
+        format!("{}/{path}", base.trim_end_matches('/'))
+    }
+}
+
+/// A Minecraft session token, as given by the Minecraft services.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinecraftToken {
+    pub access_token: String,
+    /// Seconds since the Unix epoch
+    pub expires_at: u64,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[derive(Deserialize)]
+struct DeviceCodeResponse {
+    user_code: String,
+    device_code: String,
+    verification_uri: String,
+    expires_in: u64,
+    interval: u64,
+}
+
+#[derive(Deserialize)]
+struct OAuthError {
+    error: String,
+    error_description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UserHash {
+    uhs: String,
+}
+
+#[derive(Deserialize)]
+struct DisplayClaims {
+    xui: Vec<UserHash>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct XboxTokenResponse {
+    token: String,
+    display_claims: DisplayClaims,
+}
+
+#[derive(Deserialize)]
+struct MinecraftAuthResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// Turns an error response into a message, from its OAuth error if it has one.
+async fn error_message(response: reqwest::Response) -> String {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    match serde_json::from_str::<OAuthError>(&body) {
+        Ok(error) => error.error_description.unwrap_or(error.error),
+        Err(_) if body.is_empty() => status.to_string(),
+        Err(_) => format!("{status}: {body}")
+    }
+}
+
+/// Sends a request, turning error responses into their message and parsing the body of
+/// successful ones.
+async fn send<T: DeserializeOwned>(request: reqwest::RequestBuilder) -> Result<T, String> {
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(error_message(response).await)
+    }
+    response.json().await.map_err(|e| format!("Invalid response: {e}"))
+}
+
+async fn get_device_code(client: &reqwest::Client, endpoints: &Endpoints) -> Result<DeviceCodeResponse, String> {
+    send(client
+        .post(Endpoints::endpoint(&endpoints.live, "oauth20_connect.srf"))
+        .form(&[("client_id", CLIENT_ID), ("response_type", "device_code"), ("scope", SCOPE)])
+    ).await
+}
+
+/// Polls for the MSA token until the user has entered the code, or the timeout has elapsed.
+async fn get_ms_token(
+    client: &reqwest::Client,
+    endpoints: &Endpoints,
+    credentials: &VerificationInfo,
+    timeout: Duration
+) -> Result<ExpiringValue<AccessTokenResponse>, String> {
+    let deadline = Instant::now() + timeout;
+    let interval = Duration::from_secs(credentials.interval).max(MIN_POLL_INTERVAL);
+    while Instant::now() < deadline {
+        tokio::time::sleep(interval).await;
+        let response = client
+            .post(Endpoints::endpoint(&endpoints.live, "oauth20_token.srf"))
+            .form(&[
+                ("client_id", CLIENT_ID),
+                ("device_code", credentials.device.as_str()),
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code")
+            ])
+            .send().await
+            .map_err(|e| e.to_string())?;
+        if response.status().is_success() {
+            let data: AccessTokenResponse = response.json().await
+                .map_err(|e| format!("Invalid response: {e}"))?;
+            return Ok(ExpiringValue { expires_at: now() + data.expires_in, data })
+        }
+        let body = response.text().await.unwrap_or_default();
+        match serde_json::from_str::<OAuthError>(&body) {
+            Ok(error) if matches!(error.error.as_str(), "authorization_pending" | "slow_down") => continue,
+            Ok(error) => return Err(error.error_description.unwrap_or(error.error)),
+            Err(_) => return Err(format!("Invalid response: {body}"))
+        }
+    }
+    Err("Authentication timed out".to_string())
+}
+
+/// Exchanges an MSA token for an Xbox Live token, then for an XSTS token, returning it along
+/// with the user hash.
+async fn get_xsts_token(client: &reqwest::Client, endpoints: &Endpoints, msa_token: &str) -> Result<(String, String), String> {
+    let xbl: XboxTokenResponse = send(client
+        .post(Endpoints::endpoint(&endpoints.xbox_live, "user/authenticate"))
+        .header("x-xbl-contract-version", "1")
+        .json(&json!({
+            "Properties": {
+                "AuthMethod": "RPS",
+                "SiteName": "user.auth.xboxlive.com",
+                "RpsTicket": msa_token
+            },
+            "RelyingParty": "http://auth.xboxlive.com",
+            "TokenType": "JWT"
+        }))
+    ).await.map_err(|e| format!("Xbox Live authentication failed: {e}"))?;
+    let xsts: XboxTokenResponse = send(client
+        .post(Endpoints::endpoint(&endpoints.xsts, "xsts/authorize"))
+        .json(&json!({
+            "Properties": {
+                "SandboxId": "RETAIL",
+                "UserTokens": [xbl.token]
+            },
+            "RelyingParty": "rp://api.minecraftservices.com/",
+            "TokenType": "JWT"
+        }))
+    ).await.map_err(|e| format!("XSTS authorization failed: {e}"))?;
+    let user_hash = xsts.display_claims.xui.into_iter().next()
+        .ok_or("XSTS authorization failed: no user hash")?
+        .uhs;
+    Ok((xsts.token, user_hash))
+}
+
+/// Gets a Minecraft session token with an MSA token, through Xbox Live.
+///
+/// # Returns
+/// The Minecraft session token, or the error of whichever step failed as a String
+pub async fn get_minecraft_token(
+    client: &reqwest::Client,
+    endpoints: &Endpoints,
+    msa_token: &str
+) -> Result<MinecraftToken, String> {
+    let (xsts_token, user_hash) = get_xsts_token(client, endpoints, msa_token).await?;
+    let response: MinecraftAuthResponse = send(client
+        .post(Endpoints::endpoint(&endpoints.minecraft_services, "authentication/login_with_xbox"))
+        .json(&json!({ "identityToken": format!("XBL3.0 x={user_hash};{xsts_token}") }))
+    ).await.map_err(|e| format!("Minecraft authentication failed: {e}"))?;
+    Ok(MinecraftToken {
+        access_token: response.access_token,
+        expires_at: now() + response.expires_in
+    })
+}
+
+/// Gets the profile of the account a Minecraft session token belongs to.
+///
+/// # Returns
+/// The profile, [`None`] if the account doesn't own Minecraft, or an error as a String
+pub async fn get_profile(
+    client: &reqwest::Client,
+    endpoints: &Endpoints,
+    access_token: &str
+) -> Result<Option<ProfileResponse>, String> {
+    let response = client
+        .get(Endpoints::endpoint(&endpoints.minecraft_services, "minecraft/profile"))
+        .bearer_auth(access_token)
+        .send().await
+        .map_err(|e| e.to_string())?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None)
+    }
+    if !response.status().is_success() {
+        return Err(error_message(response).await)
+    }
+    response.json().await
+        .map(Some)
+        .map_err(|e| format!("Invalid profile: {e}"))
+}
+
+#[derive(Debug, Clone)]
+pub struct VerificationInfo {
+    pub code: String,
+    pub uri: String,
+    pub device: String,
+    pub expiration: u64,
+    pub interval: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinecraftProfile {
+    pub uuid: Uuid,
+    pub username: String,
+    pub skins: Option<Vec<serde_json::Value>>,
+    pub capes: Option<Vec<serde_json::Value>>,
+    pub authenticated: bool
+}
+
+impl From<&ProfileResponse> for MinecraftProfile {
+    fn from(value: &ProfileResponse) -> Self {
+        Self {
+            uuid: value.id,
+            username: value.name.clone(),
+            skins: Some(value.skins.clone()),
+            capes: Some(value.capes.clone()),
+            authenticated: true
+        }
+    }
+}
+
+impl MinecraftProfile {
+    pub fn with_username(username: String) -> Self {
+        // generate uuid from `OfflinePlayer:<username>`
+        let uuid = Uuid::new_v3(
+            &Uuid::NAMESPACE_X500,
+            format!("OfflinePlayer:{username}")
+                .as_bytes()
+        );
+        info!("Generated offline UUID for {username}: {uuid}");
+
+        Self {
+            uuid,
+            username,
+            skins: None,
+            capes: None,
+            authenticated: false
+        }
+    }
+}
+
+/// Attempts to refresh the provided MSA token, using its refresh token.
+/// This method does not verify whether the MSA token has expired or not.
+///
+/// # Parameters
+/// * `state_callback` - a callback passing a reference to [`AuthState`] as an argument which
+///   can be useful to display the current state of the authentication process to the user.
+///   Provide an empty callback `|_| {}` if you don't want to display anything.
+/// * `client` - the HTTP client to send the request with
+/// * `endpoints` - the services to authenticate with, see [`Endpoints`]
+/// * `msa` - the MSA token to refresh
+///
+/// # Returns
+/// A result containing a valid MSA token or the error of the server as a String
+pub async fn refresh_ms<Scb>(
+    mut state_callback: Scb,
+    client: &reqwest::Client,
+    endpoints: &Endpoints,
+    msa: &ExpiringValue<AccessTokenResponse>,
+) -> Result<ExpiringValue<AccessTokenResponse>, String>
+where
+    Scb: FnMut(&AuthState),
+{
+    if cfg!(debug_assertions) { debug!("Requested token refresh...") }
+    let response = send::<AccessTokenResponse>(client
+        .post(Endpoints::endpoint(&endpoints.live, "oauth20_token.srf"))
+        .form(&[
+            ("scope", SCOPE),
+            ("client_id", CLIENT_ID),
+            ("grant_type", "refresh_token"),
+            ("refresh_token", msa.data.refresh_token.as_str())
+        ])
+    ).await;
+    match response.map(|data| ExpiringValue { expires_at: now() + data.expires_in, data }) {
+        Ok(msa) => {
+            state_callback(&AuthState::Working("Successfully refreshed MSA token".to_owned()));
+            Ok(msa)
+        },
+        Err(e) => {
+            state_callback(&AuthState::Error(format!(
+                "Failed to refresh MSA token. Re-authentication is required. ({e})"
+            )));
+            Err(e)
+        }
+    }
+}
+
+/// Refreshes the provided MSA token, then gets a new Minecraft session token with it, as the
+/// session token can't be refreshed on its own.
+///
+/// # Parameters
+/// * `state_callback`, `client`, `endpoints` - see [`refresh_ms`]
+/// * `msa` - the MSA token to refresh
+///
+/// # Returns
+/// A result containing the refreshed MSA token along with the new Minecraft session token, or
+/// the error of whichever step failed as a String
+pub async fn refresh_session<Scb>(
+    mut state_callback: Scb,
+    client: &reqwest::Client,
+    endpoints: &Endpoints,
+    msa: &ExpiringValue<AccessTokenResponse>,
+) -> Result<(ExpiringValue<AccessTokenResponse>, MinecraftToken), String>
+where
+    Scb: FnMut(&AuthState),
+{
+    let msa = refresh_ms(&mut state_callback, client, endpoints, msa).await?;
+    match get_minecraft_token(client, endpoints, &msa.data.access_token).await {
+        Ok(token) => {
+            state_callback(&AuthState::Working("Successfully refreshed Minecraft session token".to_owned()));
+            Ok((msa, token))
+        },
+        Err(e) => {
+            state_callback(&AuthState::Error(format!(
+                "Failed to get a new Minecraft session token. ({e})"
+            )));
+            Err(e)
+        }
+    }
+}
+
+pub struct Authentication {
+    client: reqwest::Client,
+    endpoints: Endpoints,
+    pub credentials: Option<VerificationInfo>,
+    pub msa: Option<ExpiringValue<AccessTokenResponse>>,
+    pub access_token: Option<MinecraftToken>,
+    pub profile: Option<MinecraftProfile>,
+    pub state: AuthState,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum AuthState {
+    /// The authenticator is currently working on something.
+    /// Contains a user-friendly message about what's currently going on.
+    Working(String),
+    /// The authentication process has completed successfully.
+    /// Contains the access token of the authenticated Minecraft session.
+    Success(String),
+    /// The authentication process has failed.
+    /// Contains a user-friendly error message as a String
+    Error(String),
+}
+
+impl Display for AuthState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            AuthState::Working(msg) => msg.clone(),
+            AuthState::Success(token) => format!("Got Minecraft session token: [{token}]"),
+            AuthState::Error(msg) => msg.clone(),
+        };
+        write!(f, "{str}")
+    }
+}
+
+pub struct AuthTimeout(u64);
+
+impl AuthTimeout {
+    pub fn new(timeout: u64) -> Self {
+        Self(timeout)
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.0)
+    }
+}
+
+impl From<Duration> for AuthTimeout {
+    fn from(duration: Duration) -> Self {
+        Self::new(duration.as_millis() as u64)
+    }
+}
+
+impl Default for AuthTimeout {
+    fn default() -> Self {
+        Duration::from_secs(90).into()
+    }
+}
+
+impl Default for Authentication {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Authentication {
+    /// Creates a new asynchronous authentication client
+    pub fn new() -> Self {
+        Self::with_client(reqwest::Client::new(), Endpoints::default())
+    }
+
+    /// Creates an authentication client sending its requests with `client` to `endpoints`,
+    /// e.g. a local stand-in for the Microsoft services.
+    pub fn with_client(client: reqwest::Client, endpoints: Endpoints) -> Self {
+        Self {
+            client,
+            endpoints,
+            credentials: None,
+            msa: None,
+            profile: None,
+            access_token: None,
+            state: AuthState::Working("Authentication started, waiting for requests".to_string()),
+        }
+    }
+
+    #[allow(dead_code)] // used in tests
+    pub fn state_is_final(&self) -> bool {
+        matches!(self.state, AuthState::Success(_) | AuthState::Error(_))
+    }
+
+    /// Gain access info (Microsoft verification URI and user verification access code)
+    /// for the user to log in into their Microsoft account and allow the authenticator to
+    /// continue with the login process.
+    ///
+    /// # Parameters
+    /// * `state_callback` - a callback passing a reference to [`AuthState`] as an argument which
+    ///   can be useful to display the current state of the authentication process to the user.
+    ///   Provide an empty callback `|_| {}` if you don't want to display anything.
+    ///
+    /// # Returns
+    /// The last [`AuthState`] the authenticator was left on, either an [`AuthState::Working`]
+    /// containing the access info or [`AuthState::Error`] containing the error message.
+    pub async fn get_access_info<Scb>(&mut self, mut state_callback: Scb) -> &AuthState
+    where
+        Scb: FnMut(&AuthState),
+    {
+        self.state = AuthState::Working("Getting access info...".to_string());
+        state_callback(&self.state);
+        match get_device_code(&self.client, &self.endpoints).await {
+            Ok(code_resp) => {
+                self.credentials = Some(VerificationInfo {
+                    code: code_resp.user_code,
+                    uri: code_resp.verification_uri,
+                    device: code_resp.device_code,
+                    expiration: code_resp.expires_in,
+                    interval: code_resp.interval,
+                });
+                self.state = AuthState::Working("Got MS access credentials.".to_string());
+                state_callback(&self.state);
+            }
+            Err(err) => {
+                self.state = AuthState::Error(err);
+                state_callback(&self.state);
+            }
+        }
+        &self.state
+    }
+
+    /// Proceeds with the authentication process, authenticating the Microsoft account and
+    /// gaining an access token.
+    ///
+    /// You must specify a timeout [`AuthTimeout`] after which this authentication step is
+    /// aborted if *the user does not complete the previous step* within the specified timeout.
+    ///
+    /// # Parameters
+    /// * `timeout` - The timeout for the *user verification process* after which this
+    ///   authentication step is aborted, default is 90s; see [`AuthTimeout`]
+    /// * `state_callback` - a callback passing a reference to [`AuthState`] as an argument which
+    ///   can be useful to display the current state of the authentication process to the user.
+    ///   Provide an empty callback `|_| {}` if you don't want to display anything.
+    ///
+    /// # Returns
+    /// The last [`AuthState`] the authenticator was left on, either an [`AuthState::Working`]
+    /// containing the access info or [`AuthState::Error`] containing the error message.
+    pub async fn authenticate_ms<Scb>(
+        &mut self,
+        timeout: AuthTimeout,
+        mut state_callback: Scb,
+    ) -> &AuthState
+    where
+        Scb: FnMut(&AuthState),
+    {
+        self.state = AuthState::Working("Waiting for User authentication...".to_string());
+        state_callback(&self.state);
+        println!("{:#?}", self.credentials);
+        if let Some(resp) = &self.credentials {
+            self.state = AuthState::Working("Authenticating Microsoft account...".to_string());
+            state_callback(&self.state);
+            match get_ms_token(&self.client, &self.endpoints, resp, timeout.duration()).await {
+                Ok(msa) => {
+                    self.msa = Some(msa);
+                    self.state = AuthState::Working(
+                        "Got Microsoft access token, successfully authenticated!".to_string(),
+                    );
+                    state_callback(&self.state);
+                }
+                Err(err) => {
+                    self.state = AuthState::Error(err);
+                    state_callback(&self.state);
+                }
+            }
+        } else {
+            self.state = AuthState::Error("No access info to authenticate with".to_string());
+            state_callback(&self.state);
+        }
+        &self.state
+    }
+
+    /// The last step in the authentication process, authenticating the Minecraft session and
+    /// getting a session token.
+    ///
+    /// # Parameters
+    /// * `state_callback` - a callback passing a reference to [`AuthState`] as an argument which
+    ///   can be useful to display the current state of the authentication process to the user.
+    ///   Provide an empty callback `|_| {}` if you don't want to display anything.
+    ///
+    /// # Returns
+    /// The last [`AuthState`] the authenticator was left on, either an [`AuthState::Success`]
+    /// containing the Minecraft session token or [`AuthState::Error`] containing the error
+    /// message. If the account doesn't own Minecraft, the session token is still kept but
+    /// [`Authentication::profile`] is left empty, and the state is an error.
+    pub async fn authenticate_minecraft<Scb>(&mut self, mut state_callback: Scb) -> &AuthState
+    where
+        Scb: FnMut(&AuthState),
+    {
+        self.state = AuthState::Working("Waiting for Microsoft authentication...".to_string());
+        state_callback(&self.state);
+        if let Some(msa) = &self.msa {
+            self.state = AuthState::Working("Authenticating Minecraft session...".to_string());
+            state_callback(&self.state);
+            match get_minecraft_token(&self.client, &self.endpoints, &msa.data.access_token).await {
+                Ok(token) => {
+                    self.state = AuthState::Working("Got session token, retrieving profile...".to_string());
+                    state_callback(&self.state);
+                    match get_profile(&self.client, &self.endpoints, &token.access_token).await {
+                        Ok(Some(profile)) => {
+                            self.profile = Some(MinecraftProfile::from(&profile));
+                            self.state = AuthState::Success(token.access_token.clone());
+                            self.access_token = Some(token);
+                        }
+                        Ok(None) => {
+                            self.state = AuthState::Error("The account doesn't own Minecraft".to_string());
+                            self.access_token = Some(token);
+                        }
+                        Err(err) => {
+                            self.state = AuthState::Error(format!("Failed to retrieve the profile: {err}"));
+                        }
+                    }
+                    state_callback(&self.state);
+                }
+                Err(err) => {
+                    self.state = AuthState::Error(err);
+                    state_callback(&self.state);
+                }
+            }
+        } else {
+            self.state = AuthState::Error("No MSA credentials to authenticate with".to_string());
+            state_callback(&self.state);
+        }
+        &self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::auth::{AuthState, AuthTimeout, Authentication};
+    use std::time::Duration;
+
+    // the tests below go through the real services and wait for someone to enter the code;
+    // tests/microsoft_auth.rs covers the same steps against a stand-in
+
+    #[tokio::test]
+    #[ignore = "needs a Microsoft account and someone to enter the code"]
+    async fn test_full_process() {
+        let mut auth = Authentication::new();
+        auth.get_access_info(|_| {}).await;
+        println!("Credentials: {:?}", auth.credentials);
+        auth.authenticate_ms(Default::default(), |_| {}).await;
+        println!("MSA: {:?}", auth.msa);
+        auth.authenticate_minecraft(|_| {}).await;
+        println!("Result: {:?}", auth.access_token);
+        assert!(auth.state_is_final());
+        assert_eq!(
+            auth.state,
+            AuthState::Success(format!(
+                "Got Minecraft session token: [{}]",
+                auth.access_token.unwrap().access_token.clone()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs access to the Microsoft services"]
+    async fn test_timeout() {
+        let mut auth = Authentication::new();
+        auth.get_access_info(|_| {}).await;
+        println!("Credentials: {:?}", auth.credentials);
+        auth.authenticate_ms(AuthTimeout::from(Duration::from_secs(1)), |_| {})
+            .await;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(auth.state_is_final());
+        assert_eq!(
+            auth.state,
+            AuthState::Error("Authentication timed out".to_string())
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Microsoft account and someone to enter the code"]
+    async fn test_state_callback() {
+        let mut auth = Authentication::new();
+        auth.get_access_info(|state| {
+            println!("Creds: {:?}", state);
+        })
+        .await;
+        auth.authenticate_ms(Default::default(), |state| {
+            println!("MS auth: {:?}", state);
+        })
+        .await;
+        auth.authenticate_minecraft(|state| {
+            println!("MC auth: {:?}", state);
+        })
+        .await;
+    }
+}
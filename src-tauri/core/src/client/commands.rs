@@ -0,0 +1,267 @@
+use std::collections::BTreeMap;
+use azalea_chat::style::{Ansi, ChatFormatting};
+use crate::client::{movement::Movement, ClientInstance};
+
+/// The prefix of instance commands, unless configured otherwise.
+pub const DEFAULT_PREFIX: &str = ".";
+
+/// Runs a command on an instance, with the arguments following its name. Output is meant to be
+/// pushed to the instance's chat; the error, if any, is shown along with the command's usage.
+pub type CommandHandler = fn(&mut ClientInstance, &[&str]) -> Result<(), String>;
+
+/// A client-side command, run locally instead of being sent to the server.
+#[derive(Clone, Copy)]
+pub struct InstanceCommand {
+    pub name: &'static str,
+    /// The arguments of the command, e.g. `<x> <y> <z>`
+    pub usage: &'static str,
+    pub description: &'static str,
+    pub handler: CommandHandler,
+}
+
+/// A chat input, as parsed by [`CommandDispatcher::parse`].
+#[derive(Debug, PartialEq)]
+pub enum Input<'a> {
+    /// A message (or server command) to send as is.
+    Message(&'a str),
+    Command { name: &'a str, args: Vec<&'a str> },
+}
+
+/// Intercepts the chat inputs of an instance starting with its prefix, and runs them as
+/// [`InstanceCommand`]s. Inputs starting with the prefix twice are sent to the server without
+/// it, e.g. `..hello` sends `.hello`.
+#[derive(Clone)]
+pub struct CommandDispatcher {
+    prefix: String,
+    commands: BTreeMap<&'static str, InstanceCommand>,
+}
+
+impl Default for CommandDispatcher {
+    /// A dispatcher with the built-in commands and the [`DEFAULT_PREFIX`].
+    fn default() -> Self {
+        let mut dispatcher = Self::new(DEFAULT_PREFIX.to_string());
+        for command in BUILTINS {
+            dispatcher.register(*command);
+        }
+        dispatcher
+    }
+}
+
+impl CommandDispatcher {
+    /// A dispatcher without any command.
+    pub fn new(prefix: String) -> Self {
+        Self {
+            prefix,
+            commands: BTreeMap::new()
+        }
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn set_prefix(&mut self, prefix: String) {
+        self.prefix = prefix;
+    }
+
+    /// Registers a command, replacing any command with the same name.
+    pub fn register(&mut self, command: InstanceCommand) {
+        self.commands.insert(command.name, command);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&InstanceCommand> {
+        self.commands.get(name)
+    }
+
+    /// The registered commands, sorted by name.
+    pub fn commands(&self) -> impl Iterator<Item = &InstanceCommand> {
+        self.commands.values()
+    }
+
+    pub fn parse<'a>(&self, input: &'a str) -> Input<'a> {
+        let Some(rest) = input.strip_prefix(self.prefix.as_str()) else {
+            return Input::Message(input)
+        };
+        if rest.starts_with(self.prefix.as_str()) {
+            return Input::Message(rest)
+        }
+        let mut words = rest.split_whitespace();
+        match words.next() {
+            Some(name) => Input::Command { name, args: words.collect() },
+            None => Input::Message(input)
+        }
+    }
+}
+
+fn red() -> String {
+    Ansi::rgb(ChatFormatting::Red.color().unwrap())
+}
+
+/// Runs a chat input on an instance if it is a command, returning the message to send otherwise.
+pub(crate) fn dispatch(instance: &mut ClientInstance, input: &str) -> Option<String> {
+    let (name, args) = match instance.commands().parse(input) {
+        Input::Message(message) => return Some(message.to_string()),
+        Input::Command { name, args } => (name, args)
+    };
+    let prefix = instance.commands().prefix().to_string();
+    let Some(command) = instance.commands().get(name).copied() else {
+        instance.chat().push(format!("{}Unknown command '{name}', run '{prefix}help' for a list of commands.", red()));
+        return None
+    };
+    if let Err(e) = (command.handler)(instance, &args) {
+        instance.chat().push(format!("{}{e}", red()));
+        instance.chat().push(format!("{}Usage: {prefix}{} {}", red(), command.name, command.usage));
+    }
+    None
+}
+
+/// The commands every instance starts with.
+const BUILTINS: &[InstanceCommand] = &[
+    InstanceCommand {
+        name: "help",
+        usage: "",
+        description: "Lists the available commands",
+        handler: help
+    },
+    InstanceCommand {
+        name: "list",
+        usage: "",
+        description: "Lists the players online",
+        handler: list
+    },
+    InstanceCommand {
+        name: "pos",
+        usage: "",
+        description: "Shows the position of the client",
+        handler: pos
+    },
+    InstanceCommand {
+        name: "health",
+        usage: "",
+        description: "Shows the health, food and experience of the client",
+        handler: health
+    },
+    InstanceCommand {
+        name: "goto",
+        usage: "<x> <y> <z>",
+        description: "Walks to the given block",
+        handler: goto
+    },
+    InstanceCommand {
+        name: "stop",
+        usage: "",
+        description: "Stops walking",
+        handler: stop
+    },
+    InstanceCommand {
+        name: "disconnect",
+        usage: "",
+        description: "Disconnects from the server",
+        handler: disconnect
+    },
+    InstanceCommand {
+        name: "reconnect",
+        usage: "",
+        description: "Reconnects to the server",
+        handler: reconnect
+    },
+];
+
+fn help(instance: &mut ClientInstance, _: &[&str]) -> Result<(), String> {
+    let prefix = instance.commands().prefix().to_string();
+    let lines: Vec<_> = instance.commands().commands()
+        .map(|command| match command.usage {
+            "" => format!(" - {prefix}{}: {}", command.name, command.description),
+            usage => format!(" - {prefix}{} {usage}: {}", command.name, command.description),
+        })
+        .collect();
+    instance.chat().push("Available commands:".to_string());
+    for line in lines {
+        instance.chat().push(line);
+    }
+    Ok(())
+}
+
+fn list(instance: &mut ClientInstance, _: &[&str]) -> Result<(), String> {
+    for line in instance.tab_list().lines() {
+        instance.chat().push(line);
+    }
+    Ok(())
+}
+
+fn pos(instance: &mut ClientInstance, _: &[&str]) -> Result<(), String> {
+    let t = instance.telemetry().ok_or("The client is not in game.")?;
+    instance.chat().push(format!("Position: {:.1} {:.1} {:.1} in {} (yaw {:.0}, pitch {:.0})",
+                                 t.x, t.y, t.z, t.dimension, t.yaw, t.pitch));
+    Ok(())
+}
+
+fn health(instance: &mut ClientInstance, _: &[&str]) -> Result<(), String> {
+    let t = instance.telemetry().ok_or("The client is not in game.")?;
+    instance.chat().push(format!("Health: {:.1}  Food: {} ({:.1} saturation)  Level: {} ({:.0}%)",
+                                 t.health, t.food, t.saturation, t.experience.level,
+                                 t.experience.progress * 100.0));
+    Ok(())
+}
+
+fn goto(instance: &mut ClientInstance, args: &[&str]) -> Result<(), String> {
+    let [x, y, z] = args else {
+        return Err("Expected the coordinates of a block.".to_string())
+    };
+    let coordinate = |value: &str| value.parse::<i32>()
+        .map_err(|_| format!("Invalid coordinate: {value}"));
+    if !instance.is_running() {
+        return Err("The client is not connected.".to_string())
+    }
+    instance.send_movement(Movement::Goto { x: coordinate(x)?, y: coordinate(y)?, z: coordinate(z)? });
+    Ok(())
+}
+
+fn stop(instance: &mut ClientInstance, _: &[&str]) -> Result<(), String> {
+    if !instance.is_running() {
+        return Err("The client is not connected.".to_string())
+    }
+    instance.send_movement(Movement::Stop);
+    Ok(())
+}
+
+fn disconnect(instance: &mut ClientInstance, _: &[&str]) -> Result<(), String> {
+    instance.disconnect_notify()?;
+    instance.chat().push("Disconnecting...".to_string());
+    Ok(())
+}
+
+fn reconnect(instance: &mut ClientInstance, _: &[&str]) -> Result<(), String> {
+    instance.chat().push("Reconnecting...".to_string());
+    instance.connect();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::commands::{CommandDispatcher, Input, InstanceCommand};
+
+    #[test]
+    fn command_parsing() {
+        let mut dispatcher = CommandDispatcher::default();
+        assert_eq!(dispatcher.parse("hello"), Input::Message("hello"));
+        assert_eq!(dispatcher.parse("/tp 1 2 3"), Input::Message("/tp 1 2 3"));
+        assert_eq!(dispatcher.parse(".goto 1  2 3"), Input::Command { name: "goto", args: vec!["1", "2", "3"] });
+        assert_eq!(dispatcher.parse("..hello"), Input::Message(".hello"));
+        assert_eq!(dispatcher.parse("."), Input::Message("."));
+
+        dispatcher.set_prefix("!".to_string());
+        assert_eq!(dispatcher.parse(".list"), Input::Message(".list"));
+        assert_eq!(dispatcher.parse("!list"), Input::Command { name: "list", args: vec![] });
+
+        assert!(dispatcher.get("wave").is_none());
+        dispatcher.register(InstanceCommand {
+            name: "wave",
+            usage: "",
+            description: "Waves",
+            handler: |_, _| Ok(())
+        });
+        assert!(dispatcher.get("wave").is_some());
+        assert_eq!(dispatcher.commands().next().unwrap().name, "disconnect");
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex}
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use crate::client::hooks::{Payload, Publisher};
+
+/// How many chat lines are kept in memory for each instance.
+pub const CHAT_CAPACITY: usize = 1000;
+
+/// A chat line of an instance, numbered in the order it was received.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ChatLine {
+    /// Increases by one with every line of the instance, starting from 1
+    pub seq: u64,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: i64,
+    pub message: String,
+}
+
+#[derive(Default)]
+struct ChatRing {
+    lines: VecDeque<ChatLine>,
+    last_seq: u64,
+}
+
+/// A bounded buffer of the last [`CHAT_CAPACITY`] chat lines of an instance, shared between
+/// the instance and its client thread. It outlives reconnections, so that a frontend can
+/// backfill whatever it missed with [`ChatBuffer::since`].
+///
+/// Every line is also published to the event channel as it is added.
+#[derive(Clone, Default)]
+pub struct ChatBuffer {
+    ring: Arc<Mutex<ChatRing>>,
+    /// Wakes up the tasks waiting for new lines, see [`ChatBuffer::wait_since`]
+    appended: Arc<Notify>,
+    publisher: Option<Publisher>,
+}
+
+impl ChatBuffer {
+    pub fn new(publisher: Publisher) -> Self {
+        Self {
+            ring: Arc::default(),
+            appended: Arc::default(),
+            publisher: Some(publisher)
+        }
+    }
+
+    /// Appends a line and publishes it, waiting for room in the event channel.
+    ///
+    /// Used by the client thread, so that a flood of chat slows the client down rather than
+    /// dropping lines.
+    pub async fn send(&self, message: String) {
+        let line = self.record(message);
+        if let Some(publisher) = &self.publisher {
+            publisher.publish(Payload::Chat { lines: vec![line] }).await;
+        }
+    }
+
+    /// Appends a line and publishes it, without waiting for room in the event channel.
+    pub fn push(&self, message: String) {
+        let line = self.record(message);
+        if let Some(publisher) = &self.publisher {
+            publisher.try_publish(Payload::Chat { lines: vec![line] });
+        }
+    }
+
+    /// Appends a line, evicting the oldest one if the buffer is full.
+    fn record(&self, message: String) -> ChatLine {
+        let line = {
+            let mut ring = self.ring.lock().unwrap();
+            ring.last_seq += 1;
+            let line = ChatLine {
+                seq: ring.last_seq,
+                timestamp: Utc::now().timestamp_millis(),
+                message
+            };
+            if ring.lines.len() >= CHAT_CAPACITY {
+                ring.lines.pop_front();
+            }
+            ring.lines.push_back(line.clone());
+            line
+        };
+        self.appended.notify_waiters();
+        line
+    }
+
+    /// All buffered lines after the sequence number `seq`, oldest first.
+    /// Pass `0` to get the whole buffer.
+    pub fn since(&self, seq: u64) -> Vec<ChatLine> {
+        let ring = self.ring.lock().unwrap();
+        let start = ring.lines.partition_point(|line| line.seq <= seq);
+        ring.lines.range(start..).cloned().collect()
+    }
+
+    /// Waits until at least one line is appended after the sequence number `seq`, and returns
+    /// all of them, oldest first. Returns right away if there already are such lines.
+    pub async fn wait_since(&self, seq: u64) -> Vec<ChatLine> {
+        loop {
+            // registered before checking the buffer, so that no line can slip in between
+            let appended = self.appended.notified();
+            let lines = self.since(seq);
+            if !lines.is_empty() {
+                return lines
+            }
+            appended.await;
+        }
+    }
+
+    /// The sequence number of the last line pushed, `0` if none.
+    pub fn last_seq(&self) -> u64 {
+        self.ring.lock().unwrap().last_seq
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::hooks::chatlog::{ChatBuffer, CHAT_CAPACITY};
+
+    #[test]
+    fn chat_buffer_order() {
+        let chat = ChatBuffer::default();
+        for i in 0..CHAT_CAPACITY + 5 {
+            chat.push(format!("message {i}"));
+        }
+        let all = chat.since(0);
+        assert_eq!(all.len(), CHAT_CAPACITY);
+        assert_eq!(all[0].seq, 6);
+        assert_eq!(all[0].message, "message 5");
+
+        let last = chat.last_seq();
+        let missed = chat.since(last - 2);
+        assert_eq!(missed.iter().map(|line| line.seq).collect::<Vec<_>>(), [last - 1, last]);
+        assert!(chat.since(last).is_empty());
+    }
+}
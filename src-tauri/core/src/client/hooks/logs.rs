@@ -0,0 +1,288 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use log::warn;
+use serde::Serialize;
+use uuid::Uuid;
+use crate::client::{
+    hooks::{Event, EventSink, Payload},
+    network::ConnectionState
+};
+
+/// The directory, relative to the data directory, holding the logs of every instance.
+pub const LOGS_DIR: &str = "logs";
+
+/// A single line of an instance log.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct LogEntry {
+    /// Milliseconds since the Unix epoch
+    pub timestamp: i64,
+    /// The kind of event, i.e. the name of its [`Payload`]
+    pub kind: String,
+    pub message: String,
+}
+
+impl LogEntry {
+    /// The entries of an event: one per chat line, none for telemetry and events that aren't
+    /// keyed by an instance, or a single one for any other event.
+    fn from_payload(time: DateTime<Utc>, payload: &Payload) -> Vec<Self> {
+        let entry = |kind: &str, message: String| Self {
+            timestamp: time.timestamp_millis(),
+            kind: kind.to_string(),
+            message
+        };
+        match payload {
+            Payload::Chat { lines } => lines.iter()
+                .map(|line| Self {
+                    timestamp: line.timestamp,
+                    kind: "Chat".to_string(),
+                    message: line.message.clone()
+                })
+                .collect(),
+            Payload::State { state } => vec![entry("State", state.to_string())],
+            Payload::Telemetry { .. } => vec![],
+            Payload::PlayerJoin { player } => vec![entry("PlayerJoin", format!("{} joined", player.name))],
+            Payload::PlayerLeave { player } => vec![entry("PlayerLeave", format!("{} left", player.name))],
+            Payload::Movement { status } => vec![entry("Movement", status.to_string())],
+            Payload::ReauthRequired { .. } => vec![],
+            Payload::Macro { name, step, steps, status, .. } => {
+                vec![entry("Macro", format!("{name} step {step}/{steps}: {status}"))]
+            }
+        }
+    }
+
+    /// Parses a line written by [`LogSink`], in the form `<RFC 3339 time> [<kind>] <message>`.
+    fn parse(line: &str) -> Option<Self> {
+        let (time, rest) = line.split_once(' ')?;
+        let (kind, message) = rest.strip_prefix('[')?.split_once("] ")?;
+        Some(Self {
+            timestamp: DateTime::parse_from_rfc3339(time).ok()?.timestamp_millis(),
+            kind: kind.to_string(),
+            message: unescape(message)
+        })
+    }
+}
+
+/// Escapes backslashes and line breaks, so that every entry fits on a single line.
+fn escape(message: &str) -> String {
+    message.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(message: &str) -> String {
+    let mut result = String::with_capacity(message.len());
+    let mut chars = message.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => { chars.next(); result.push('\n'); }
+            ('\\', Some('\\')) => { chars.next(); result.push('\\'); }
+            _ => result.push(c)
+        }
+    }
+    result
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let time = DateTime::from_timestamp_millis(self.timestamp).unwrap_or_default();
+        write!(f, "{} [{}] {}",
+               time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+               self.kind, escape(&self.message))
+    }
+}
+
+/// The entries of a single event, sent to the writer thread of a [`LogSink`].
+struct Record {
+    key: Uuid,
+    entries: Vec<LogEntry>,
+    /// Whether the instance stopped, so its file can be closed
+    ended: bool,
+}
+
+/// An [`EventSink`] appending every instance event to the instance's log directory,
+/// `<logs>/<instance key>/<YYYY-MM-DD>.log`, rotating files daily (UTC). Each entry goes to
+/// the file of the day of its own timestamp.
+///
+/// The files are written by a dedicated thread, so that events are never held up by the disk.
+pub struct LogSink {
+    records: mpsc::Sender<Record>,
+}
+
+impl LogSink {
+    pub fn new(root: PathBuf) -> Self {
+        let (records, receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name("instance-logs".to_string())
+            .spawn(move || Writer { root, files: HashMap::new() }.run(receiver))
+            .expect("Failed to start the instance log writer");
+        Self { records }
+    }
+}
+
+impl EventSink for LogSink {
+    fn emit_event(&self, event: &Event) {
+        let entries = LogEntry::from_payload(Utc::now(), &event.payload);
+        if entries.is_empty() {
+            return
+        }
+        let ended = matches!(&event.payload, Payload::State {
+            state: ConnectionState::Disconnected { .. } | ConnectionState::Failed { .. }
+        });
+        // only fails once the writer has stopped, which it only does when the sink is dropped
+        let _ = self.records.send(Record { key: event.key, entries, ended });
+    }
+}
+
+/// The writer thread of a [`LogSink`], keeping the file of the day of every running instance.
+struct Writer {
+    root: PathBuf,
+    files: HashMap<Uuid, (NaiveDate, File)>,
+}
+
+impl Writer {
+    fn run(mut self, records: mpsc::Receiver<Record>) {
+        for record in records {
+            if let Err(e) = self.append(&record) {
+                warn!("Failed to write log of instance {}: {e}", record.key);
+            }
+            if record.ended {
+                self.files.remove(&record.key);
+            }
+        }
+    }
+
+    fn append(&mut self, record: &Record) -> io::Result<()> {
+        for entry in &record.entries {
+            let date = DateTime::from_timestamp_millis(entry.timestamp)
+                .unwrap_or_default()
+                .date_naive();
+            writeln!(self.file(record.key, date)?, "{entry}")?;
+        }
+        Ok(())
+    }
+
+    /// The file of the instance for `date`, opened on the first entry of that day or once the
+    /// date has changed, closing the files of the previous days.
+    fn file(&mut self, key: Uuid, date: NaiveDate) -> io::Result<&mut File> {
+        if !self.files.get(&key).is_some_and(|(open, _)| *open == date) {
+            self.files.retain(|_, (open, _)| *open >= date);
+            let dir = self.root.join(key.to_string());
+            fs::create_dir_all(&dir)?;
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(dir.join(format!("{date}.log")))?;
+            self.files.insert(key, (date, file));
+        }
+        Ok(&mut self.files.get_mut(&key).unwrap().1)
+    }
+}
+
+/// Reads the entries of an instance log within `from..to`, both in milliseconds since the
+/// Unix epoch, in chronological order and up to `limit` entries, skipping the first `offset`
+/// entries at `from`.
+///
+/// The next page starts from the timestamp of the last entry returned, skipping the entries
+/// returned with that timestamp: their count, plus `offset` if they all had it.
+pub fn read(dir: &Path, from: i64, offset: usize, to: i64, limit: usize) -> io::Result<Vec<LogEntry>> {
+    let day = |millis: i64| DateTime::from_timestamp_millis(millis).map(|time| time.date_naive());
+    let (first, last) = (day(from).unwrap_or(NaiveDate::MIN), day(to).unwrap_or(NaiveDate::MAX));
+
+    let mut days: Vec<NaiveDate> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().to_str()?.strip_suffix(".log")?.parse().ok())
+            .filter(|date| (first..=last).contains(date))
+            .collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e)
+    };
+    days.sort();
+
+    let mut entries = Vec::new();
+    let mut skipped = 0;
+    for date in days {
+        let file = File::open(dir.join(format!("{date}.log")))?;
+        for line in BufReader::new(file).lines() {
+            let Some(entry) = LogEntry::parse(&line?) else {
+                continue
+            };
+            if entry.timestamp == from && skipped < offset {
+                skipped += 1;
+                continue
+            }
+            if (from..to).contains(&entry.timestamp) {
+                entries.push(entry);
+                if entries.len() >= limit {
+                    return Ok(entries)
+                }
+            }
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+    use chrono::Utc;
+    use uuid::Uuid;
+    use crate::client::hooks::{chatlog::ChatLine, logs::{self, LogEntry, Record, Writer}, Payload};
+
+    #[test]
+    fn log_line_round_trip() {
+        let line = ChatLine { seq: 1, timestamp: 1_760_000_000_123, message: "<Steve> hi\nC:\\new".to_string() };
+        let entries = LogEntry::from_payload(Utc::now(), &Payload::Chat { lines: vec![line] });
+        let entry = entries[0].clone();
+        let line = entry.to_string();
+        assert_eq!(line, "2025-10-09T08:53:20.123Z [Chat] <Steve> hi\\nC:\\\\new");
+        assert_eq!(LogEntry::parse(&line), Some(entry));
+    }
+
+    #[test]
+    fn paging_keeps_entries_of_the_same_millisecond() {
+        let dir = std::env::temp_dir().join(format!("clientworks-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let entry = |message: &str| LogEntry {
+            timestamp: 1_760_000_000_123,
+            kind: "Chat".to_string(),
+            message: message.to_string()
+        };
+        let lines = ["a", "b", "c"].map(|message| entry(message).to_string()).join("\n");
+        fs::write(dir.join("2025-10-09.log"), lines).unwrap();
+
+        let first = logs::read(&dir, i64::MIN, 0, i64::MAX, 2).unwrap();
+        assert_eq!(first, vec![entry("a"), entry("b")]);
+        let second = logs::read(&dir, first[1].timestamp, 2, i64::MAX, 2).unwrap();
+        assert_eq!(second, vec![entry("c")]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn entries_go_to_the_file_of_their_own_day() {
+        let root = std::env::temp_dir().join(format!("clientworks-{}", Uuid::new_v4()));
+        let key = Uuid::new_v4();
+        let entry = |timestamp: i64, message: &str| LogEntry {
+            timestamp,
+            kind: "Chat".to_string(),
+            message: message.to_string()
+        };
+        // 2025-10-09T23:59:59.999Z and 2025-10-10T00:00:00.000Z, written as a single event
+        let (before, after) = (entry(1_760_054_399_999, "before"), entry(1_760_054_400_000, "after"));
+        let mut writer = Writer { root: root.clone(), files: HashMap::new() };
+        writer.append(&Record { key, entries: vec![before.clone(), after.clone()], ended: false }).unwrap();
+        drop(writer);
+
+        let dir = root.join(key.to_string());
+        assert_eq!(fs::read_to_string(dir.join("2025-10-09.log")).unwrap(), format!("{before}\n"));
+        assert_eq!(fs::read_to_string(dir.join("2025-10-10.log")).unwrap(), format!("{after}\n"));
+        assert_eq!(logs::read(&dir, i64::MIN, 0, i64::MAX, 10).unwrap(), vec![before, after]);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,241 @@
+mod payload;
+pub mod chatlog;
+pub mod logs;
+
+use std::collections::HashMap;
+use log::{info, warn};
+use serde::Serialize;
+use tokio::sync::{broadcast, mpsc};
+use uuid::Uuid;
+pub use payload::*;
+
+#[derive(Serialize, Clone)]
+pub struct Event {
+    pub key: Uuid,
+    pub payload: Payload
+}
+
+/// The sending ends of the event channel: a bounded queue for chat and every other frequent
+/// event, and an unbounded one reserved for the rare state transitions, which are never dropped
+/// nor held up behind a flood of chat, see [`Publisher::publish_state`].
+#[derive(Clone)]
+pub struct EventSender {
+    events: mpsc::Sender<Event>,
+    states: mpsc::UnboundedSender<Event>
+}
+
+/// The capacity of the event channel. Once full, instances publishing chat wait for room,
+/// see [`Publisher::publish`].
+pub const CHANNEL_CAPACITY: usize = 256;
+
+/// The maximum number of events received at once by the event thread, before being
+/// coalesced and emitted, see [`coalesce`].
+const BATCH_SIZE: usize = 64;
+
+/// Publishes the events of a single instance to the event channel.
+#[derive(Clone)]
+pub struct Publisher {
+    key: Uuid,
+    events: EventSender
+}
+
+impl Publisher {
+    pub fn new(key: Uuid, events: EventSender) -> Self {
+        Self { key, events }
+    }
+
+    pub fn key(&self) -> Uuid {
+        self.key
+    }
+
+    /// Publishes an event, waiting for room in the channel if it is full.
+    pub async fn publish(&self, payload: Payload) {
+        if self.events.events.send(Event { key: self.key, payload }).await.is_err() {
+            warn!("Event channel closed, dropping event of {}", self.key);
+        }
+    }
+
+    /// Publishes an event from outside of an async context, dropping it if the channel is full.
+    pub fn try_publish(&self, payload: Payload) {
+        if let Err(e) = self.events.events.try_send(Event { key: self.key, payload }) {
+            warn!("Failed to publish event of {}: {e}", self.key);
+        }
+    }
+
+    /// Publishes a state transition on the channel reserved for them, which neither waits
+    /// nor drops the event when the main channel is full.
+    pub fn publish_state(&self, payload: Payload) {
+        if self.events.states.send(Event { key: self.key, payload }).is_err() {
+            warn!("Event channel closed, dropping event of {}", self.key);
+        }
+    }
+}
+
+/// The receiving end of all instance events.
+///
+/// The Tauri frontend implements this by emitting each event to its windows, while the
+/// headless CLI simply prints them out.
+pub trait EventSink: Send + Sync + 'static {
+    fn emit_event(&self, event: &Event);
+}
+
+/// Forwards every event to both sinks.
+impl<A: EventSink, B: EventSink> EventSink for (A, B) {
+    fn emit_event(&self, event: &Event) {
+        self.0.emit_event(event);
+        self.1.emit_event(event);
+    }
+}
+
+/// An [`EventSink`] re-publishing all events to any number of subscribers,
+/// such as the WebSocket connections of the HTTP API.
+#[derive(Clone)]
+pub struct EventBroadcast(broadcast::Sender<Event>);
+
+impl Default for EventBroadcast {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBroadcast {
+    pub fn new() -> Self {
+        Self(broadcast::channel(256).0)
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.0.subscribe()
+    }
+}
+
+impl EventSink for EventBroadcast {
+    fn emit_event(&self, event: &Event) {
+        // only fails when there are no subscribers
+        let _ = self.0.send(event.clone());
+    }
+}
+
+pub struct Channel {
+    pub sender: EventSender,
+    pub thread: tokio::task::JoinHandle<()>
+}
+
+/// Starts a communication thread between the client controllers and the provided [`EventSink`].
+/// All events are emitted using the instance UUID as identifier, with a payload containing
+/// the event data as JSON, see [`Payload`]
+///
+/// Instances publish their events straight into the channel, see [`Publisher`]. Whatever is
+/// pending is received at once and coalesced before being emitted, state transitions first.
+pub fn init<S: EventSink>(sink: S) -> Channel {
+    let (events, mut events_rx) = mpsc::channel::<Event>(CHANNEL_CAPACITY);
+    let (states, mut states_rx) = mpsc::unbounded_channel::<Event>();
+    let thread = {
+        tokio::spawn(async move {
+            let mut batch = Vec::with_capacity(BATCH_SIZE);
+            loop {
+                let received = tokio::select! {
+                    biased;
+                    received = states_rx.recv_many(&mut batch, BATCH_SIZE) => received,
+                    received = events_rx.recv_many(&mut batch, BATCH_SIZE) => received,
+                };
+                // both senders are dropped together
+                if received == 0 {
+                    break
+                }
+                for event in coalesce(batch.drain(..)) {
+                    sink.emit_event(&event);
+                }
+            }
+        })
+    };
+
+    info!("Channel hooks thread started");
+    Channel {
+        sender: EventSender { events, states },
+        thread
+    }
+}
+
+/// Merges consecutive chat events of each instance into a single one, keeping the order
+/// of the events of every instance.
+fn coalesce(events: impl Iterator<Item = Event>) -> Vec<Event> {
+    let mut merged: Vec<Event> = Vec::new();
+    // the index of each instance's last event, if it is a chat event
+    let mut open_chats: HashMap<Uuid, usize> = HashMap::new();
+    for event in events {
+        let open = open_chats.get(&event.key).copied();
+        match (event.payload, open) {
+            (Payload::Chat { lines }, Some(index)) => {
+                if let Payload::Chat { lines: pending } = &mut merged[index].payload {
+                    pending.extend(lines);
+                }
+            }
+            (Payload::Chat { lines }, None) => {
+                open_chats.insert(event.key, merged.len());
+                merged.push(Event { key: event.key, payload: Payload::Chat { lines } });
+            }
+            (payload, _) => {
+                open_chats.remove(&event.key);
+                merged.push(Event { key: event.key, payload });
+            }
+        }
+    }
+    merged
+}
+
+impl Drop for Channel {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl Channel {
+    pub fn close(&mut self) {
+        self.thread.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+    use uuid::Uuid;
+    use crate::client::{
+        hooks::{chatlog::ChatLine, coalesce, Event, EventSender, Payload, Publisher},
+        network::ConnectionState
+    };
+
+    #[test]
+    fn states_bypass_full_channel() {
+        let (events, mut events_rx) = mpsc::channel(1);
+        let (states, mut states_rx) = mpsc::unbounded_channel();
+        let publisher = Publisher::new(Uuid::new_v4(), EventSender { events, states });
+        publisher.try_publish(Payload::Chat { lines: vec![] });
+        // dropped, the channel is full
+        publisher.try_publish(Payload::Chat { lines: vec![] });
+        publisher.publish_state(Payload::State { state: ConnectionState::Playing });
+
+        assert!(events_rx.try_recv().is_ok());
+        assert!(events_rx.try_recv().is_err());
+        assert!(matches!(states_rx.try_recv().unwrap().payload,
+                         Payload::State { state: ConnectionState::Playing }));
+    }
+
+    #[test]
+    fn chat_coalescing() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let chat = |key, seq| Event {
+            key,
+            payload: Payload::Chat { lines: vec![ChatLine { seq, timestamp: 0, message: String::new() }] }
+        };
+        let disconnect = |key| Event { key, payload: Payload::State { state: ConnectionState::Disconnecting } };
+
+        let merged = coalesce(vec![
+            chat(a, 1), chat(b, 1), chat(a, 2), disconnect(a), chat(a, 3), chat(b, 2)
+        ].into_iter());
+        let summary: Vec<_> = merged.iter().map(|event| match &event.payload {
+            Payload::Chat { lines } => (event.key, lines.iter().map(|line| line.seq).collect()),
+            _ => (event.key, vec![])
+        }).collect();
+        assert_eq!(summary, [(a, vec![1, 2]), (b, vec![1, 2]), (a, vec![]), (a, vec![3])]);
+    }
+}
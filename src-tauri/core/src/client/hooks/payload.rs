@@ -0,0 +1,53 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::client::{
+    hooks::chatlog::ChatLine,
+    movement::MovementStatus,
+    tablist::TabEntry,
+    telemetry::Telemetry,
+    network::ConnectionState
+};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub enum Payload {
+    /// Chat lines of the instance, oldest first, see [`super::chatlog::ChatLine`].
+    Chat { lines: Vec<ChatLine> },
+    /// The instance transitioned to a new connection state, including
+    /// [`ConnectionState::Failed`] with the error when it failed to connect.
+    State { state: ConnectionState },
+    /// Progress of a macro run on the instance; `step` counts from 1 up to `steps`.
+    Macro { run: Uuid, name: String, step: usize, steps: usize, status: MacroStatus },
+    /// Progress of the movement of the instance, see [`crate::client::movement::Movement`].
+    Movement { status: MovementStatus },
+    /// The in-game state of the instance, published at most once per second while it changes.
+    Telemetry { telemetry: Telemetry },
+    /// A player was added to the tab list of the instance, once it had joined the game.
+    PlayerJoin { player: TabEntry },
+    /// A player was removed from the tab list of the instance.
+    PlayerLeave { player: TabEntry },
+    /// The cached tokens of an account have expired and could not be refreshed, so
+    /// it must authenticate again. Unlike other events, this one is keyed by the client ID.
+    ReauthRequired { login_key: String, username: String, reason: String },
+}
+
+/// The status of a macro run on a single instance.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "status")]
+pub enum MacroStatus {
+    Running,
+    Completed,
+    Cancelled,
+    Failed { reason: String },
+}
+
+impl fmt::Display for MacroStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MacroStatus::Running => write!(f, "running"),
+            MacroStatus::Completed => write!(f, "completed"),
+            MacroStatus::Cancelled => write!(f, "cancelled"),
+            MacroStatus::Failed { reason } => write!(f, "failed ({reason})"),
+        }
+    }
+}
@@ -0,0 +1,727 @@
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    sync::{
+        Arc, Mutex
+    },
+    ops::Deref,
+    fmt::{
+        self, Formatter
+    }};
+use std::{
+    any::Any,
+    panic::AssertUnwindSafe,
+    time::Duration
+};
+use crate::{
+    api::Server,
+    client, client::{
+        AuthProtocol, Version,
+        commands::{self, CommandDispatcher},
+        hooks::{chatlog::ChatBuffer, EventSender, Payload, Publisher},
+        movement::{Movement, Navigation},
+        proxy::Proxy,
+        relay::{Relay, Upstream},
+        yggdrasil::SessionJoin,
+        telemetry::{Experience, Telemetry, TelemetryHandle},
+        tablist::{TabEntry, TabList, TabListHandle},
+        network::{ConnectionError, ConnectionHandle, ConnectionState, DisconnectReason, ReconnectPolicy, StateHandle},
+        scripting::ScriptHost,
+        version::Backend
+    }
+};
+use azalea::{
+    app::PluginGroup,
+    Account, ClientBuilder, 
+    prelude::*, AccountOpts, 
+    protocol::{
+        packets::game::ClientboundGamePacket, 
+        ServerAddress
+    }, FormattedText, DefaultPlugins,
+    DefaultBotPlugins
+};
+use azalea_chat::style::{Ansi, ChatFormatting};
+use azalea_viaversion::ViaVersionPlugin;
+use futures::FutureExt;
+use log::warn;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+impl From<Server> for ServerAddress {
+    fn from(value: Server) -> Self {
+        Self {
+            host: value.ip,
+            port: value.port
+        }
+    }
+}
+
+pub enum StateSource {
+    /// Source depends on any value from [`ClientState`] that is retrieved from azalea's events or changed by user actions
+    Client,
+    /// Source depends on thread handle availability
+    Thread,
+    /// Source depends on the client handle received from Azalea's API
+    Handle
+}
+
+impl fmt::Display for StateSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        use StateSource::*;
+        write!(f, "{}", match self { 
+            Client => "client",
+            Thread => "thread",
+            Handle => "handle",
+        })
+    }
+}
+
+impl fmt::Debug for StateSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "StateSource {{ {self} }}")
+    }
+}
+
+pub enum InstanceEndError {
+    NoHandle,
+    NoConnect(StateSource),
+    Timeout
+}
+
+impl fmt::Display for InstanceEndError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        use InstanceEndError::*;
+        
+        match self {
+            NoHandle => write!(f, "No thread handle present on client instance."),
+            NoConnect(source) => write!(f, "Client is not connected [{source}]"),
+            Timeout => write!(f, "Thread cancellation has timed out.")
+        }
+    }
+}
+
+impl fmt::Debug for InstanceEndError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "KillError {{ {self} }}")
+    }
+}
+
+impl From<InstanceEndError> for String {
+    fn from(value: InstanceEndError) -> Self {
+        format!("{value}")
+    }
+}
+
+pub struct Info {
+    pub username: String,
+    pub uuid: String,
+    pub auth: Arc<AuthProtocol>
+}
+
+type AzaleaClient = Arc<Mutex<Option<Client>>>;
+
+pub struct ClientInstance {
+    pub id: Uuid,
+    pub info: Info,
+    pub handle: Option<ConnectionHandle>, // TODO currently unused, might be discarded
+    pub target: Server,
+    pub version: Version,
+    pub reconnect: ReconnectPolicy,
+    /// The script run by the client, see [`ScriptHost`]
+    pub script: Option<PathBuf>,
+    /// The proxy the game connection goes through, see [`Relay`]
+    pub proxy: Option<Proxy>,
+    commands: CommandDispatcher,
+    state: StateHandle,
+    chat: ChatBuffer,
+    chat_inputs: ChatInputs,
+    movement_inputs: MovementInputs,
+    telemetry: TelemetryHandle,
+    tab_list: TabListHandle,
+    publisher: Publisher,
+    client: AzaleaClient,                 // TODO figure out a way to store this lol
+    account: Account,
+    pub client_thread: Option<JoinHandle<()>>
+}
+
+type ChatInputs = Arc<Mutex<VecDeque<String>>>;
+type MovementInputs = Arc<Mutex<VecDeque<Movement>>>;
+
+#[derive(Default, Clone, Component)]
+pub struct ClientState {
+    pub instance_key: Uuid,
+    pub chat: ChatBuffer,
+    pub chat_inputs: ChatInputs,
+    pub movement_inputs: MovementInputs,
+    pub navigation: Arc<Mutex<Navigation>>,
+    pub telemetry: TelemetryHandle,
+    pub tab_list: TabListHandle,
+    pub connection: StateHandle,
+    pub script: Option<Arc<ScriptHost>>,
+    pub publisher: Option<Publisher>,
+}
+
+/// 'Softly' kills the running client thread, if present. This will not abruptly abort the thread.
+///
+/// It times out the client thread for 8 seconds. If the thread fails to close by then,
+/// [`InstanceEndError::Timeout`] is returned and the handle is left in `client_thread`,
+/// so the caller can fall back to hard-killing the thread; i.e., abort it.
+///
+/// It's OK to call this after any other command; it's suggested to run this after
+/// [`ClientInstance::disconnect_notify`] to ensure a smooth disconnection.
+///
+/// # Parameters
+/// * `key` - the key of the instance to remove from the active chat logs registry - unused for now
+/// * `client_thread` - the optional client thread's `JoinHandle` to perform the operation on
+pub async fn soft_kill(_key: &Uuid, client_thread: &mut Option<JoinHandle<()>>) -> Result<(), InstanceEndError> {
+    if let Some(thread) = client_thread.as_mut() {
+        return match tokio::time::timeout(
+            Duration::from_secs(8), thread
+        ).await {
+            Ok(_) => {
+                client_thread.take();
+                Ok(())
+            },
+            Err(_) => Err(InstanceEndError::Timeout)
+        }
+        // return match tokio::join!(thread).0 {
+        //     Ok(_) => Ok(()),
+        //     Err(e) => Err(InstanceEndError::JoinError(e))
+        // };
+    }
+    Err(InstanceEndError::NoConnect(StateSource::Thread))
+}
+
+/// How long a client may stay [`ConnectionState::Connecting`] before the attempt is considered failed.
+const JOIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Completes once the client has been connecting for longer than [`JOIN_TIMEOUT`].
+/// Never completes if the client leaves the connecting state in time.
+async fn join_timeout(connection: &StateHandle) {
+    let start = tokio::time::Instant::now();
+    while connection.get() == ConnectionState::Connecting {
+        if start.elapsed() >= JOIN_TIMEOUT {
+            return
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    std::future::pending::<()>().await
+}
+
+/// Waits for `delay`, or until the client leaves the reconnecting state, e.g. because the
+/// user asked it to disconnect.
+async fn wait_reconnect(connection: &StateHandle, delay: Duration) {
+    let end = tokio::time::Instant::now() + delay;
+    while tokio::time::Instant::now() < end
+        && matches!(connection.get(), ConnectionState::Reconnecting { .. }) {
+        tokio::time::sleep(Duration::from_millis(500).min(end - tokio::time::Instant::now())).await;
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown cause".to_string()
+    }
+}
+
+/// Runs a single connection to the server, from address resolution until the client is
+/// disconnected. The outcome is left in the `connection` state.
+///
+/// With a proxy, or a session server to join in place of azalea, the client joins a [`Relay`]
+/// forwarding its connection to the server.
+async fn run_session(account: Account, target: Server, version: Version, proxy: Option<Proxy>,
+                     join: Option<SessionJoin>, state: ClientState) {
+    let connection = state.connection.clone();
+    let upstream = match proxy {
+        // the proxy resolves the server, so that its address isn't looked up locally
+        Some(proxy) => Upstream::Proxy(proxy),
+        None => match azalea::protocol::resolver::resolve_address(&ServerAddress::from(target.clone())).await {
+            Ok(destination) => Upstream::Direct(destination),
+            Err(e) => {
+                connection.fail(ConnectionError::Network(format!("Failed to resolve {target}: {e}")));
+                return
+            }
+        }
+    };
+    let relay = match (upstream, join) {
+        (Upstream::Direct(_), None) => None,
+        (upstream, join) => match Relay::start(upstream, join, target.clone()).await {
+            Ok(relay) => Some(relay),
+            Err(e) => {
+                connection.fail(ConnectionError::Network(format!("Failed to start relay: {e}")));
+                return
+            }
+        }
+    };
+    let join_target = match &relay {
+        Some(relay) => Server::new(target.name.clone(), relay.addr().ip().to_string(), relay.addr().port()),
+        None => target
+    };
+    // errors of the relay explain failures better than the client, which only sees it close
+    let fail = |error: ConnectionError| connection.fail(relay.as_ref().and_then(Relay::error).unwrap_or(error));
+    // the user may have asked to disconnect while the server was being resolved
+    if !connection.set_if(|current| *current == ConnectionState::Resolving, ConnectionState::Connecting) {
+        connection.set_if(
+            |current| *current == ConnectionState::Disconnecting,
+            ConnectionState::Disconnected { reason: DisconnectReason::User }
+        );
+        return
+    }
+
+    // azalea retries failed joins on its own without reporting them, and may panic
+    // on unexpected errors; neither should leave the instance looking connected.
+    let session = AssertUnwindSafe(async move {
+        let builder = ClientBuilder::new_without_plugins()
+            .add_plugins(DefaultPlugins.build()
+                // .disable::<bevy_log::LogPlugin>()
+            )
+            .add_plugins(DefaultBotPlugins.build());
+        // azalea speaks its native version without going through a proxy
+        let builder = match version.backend() {
+            Backend::Native => builder,
+            Backend::ViaVersion => builder.add_plugins(ViaVersionPlugin::start(version.to_string()).await),
+        };
+        let builder = builder.set_handler(handle);
+        // reconnections are handled by the instance, following its ReconnectPolicy
+        builder.set_state(state)
+            .reconnect_after(None)
+            .start(account, join_target)
+            .await
+    }).catch_unwind();
+
+    tokio::select! {
+        result = session => match result {
+            Ok(Ok(_)) => match connection.get() {
+                ConnectionState::Connecting | ConnectionState::LoggingIn => fail(
+                    ConnectionError::Network("Connection closed while logging in".to_string())
+                ),
+                current if current.is_running() => connection.set(
+                    ConnectionState::Disconnected { reason: DisconnectReason::Lost }
+                ),
+                _ => {}
+            },
+            Ok(Err(e)) => fail(ConnectionError::classify(&e.to_string())),
+            Err(panic) => connection.fail(ConnectionError::Other(
+                format!("Client thread panicked: {}", panic_message(panic.as_ref()))
+            )),
+        },
+        _ = join_timeout(&connection) => fail(ConnectionError::Network(
+            format!("Timed out after {}s while joining the server", JOIN_TIMEOUT.as_secs())
+        ))
+    }
+}
+
+fn create_azalea_account(protocol: &AuthProtocol) -> Account {
+    match protocol {
+        AuthProtocol::Offline(username) => {
+            Account {
+                username: username.clone(),
+                access_token: None,
+                // No UUID here. We calculate the UUID for the front-end only, but the
+                // actual UUID calculation for offline accounts is destined to the
+                // server implementation.
+                uuid: None,
+                account_opts: AccountOpts::Offline {
+                    username: username.clone()
+                },
+                certs: Arc::new(parking_lot::Mutex::new(None))
+            }
+        },
+        AuthProtocol::Microsoft(token, msa, profile) => {
+            Account {
+                username: profile.username.clone(),
+                access_token: Some(Arc::new(parking_lot::Mutex::new(token.clone()))),
+                uuid: Some(profile.uuid),
+                account_opts: AccountOpts::MicrosoftWithAccessToken {
+                    msa: Arc::new(parking_lot::Mutex::new(*msa.clone()))
+                },
+                certs: Arc::new(parking_lot::Mutex::new(None))
+            }
+        },
+        // joined like an offline account, the relay of the instance joins the session server
+        AuthProtocol::Yggdrasil(_, _, profile) => {
+            Account {
+                username: profile.username.clone(),
+                access_token: None,
+                uuid: Some(profile.uuid),
+                account_opts: AccountOpts::Offline {
+                    username: profile.username.clone()
+                },
+                certs: Arc::new(parking_lot::Mutex::new(None))
+            }
+        }
+    }
+}
+
+#[allow(unused)]
+async fn handle(client: Client, event: Event, state: ClientState) -> anyhow::Result<()> {
+    if let Some(script) = &state.script {
+        script.dispatch(&client, &event);
+    }
+    match event {
+        Event::Tick => {
+            if !state.connection.is_running() {
+                state.chat.send("Encountered non-running state notification on tick update, disconnecting...".to_owned()).await;
+                client.disconnect();
+                return Ok(())
+            }
+
+            {
+                let mut guard = state.chat_inputs.lock().unwrap();
+                let count = guard.len();
+                for message in guard.iter() {
+                    client.chat(message);
+                }
+                for _ in 0..count {
+                    guard.pop_front();
+                }
+            }
+
+            let statuses: Vec<_> = {
+                let mut navigation = state.navigation.lock().unwrap();
+                let mut inputs = state.movement_inputs.lock().unwrap();
+                let mut statuses: Vec<_> = inputs.drain(..)
+                    .filter_map(|movement| navigation.apply(&client, movement))
+                    .collect();
+                statuses.extend(navigation.tick(&client));
+                statuses
+            };
+            if let Some(publisher) = &state.publisher {
+                for status in statuses {
+                    publisher.publish(Payload::Movement { status }).await;
+                }
+                let telemetry = (state.connection.get() == ConnectionState::Playing)
+                    .then(|| state.telemetry.tick(&client))
+                    .flatten();
+                if let Some(telemetry) = telemetry {
+                    publisher.try_publish(Payload::Telemetry { telemetry });
+                }
+            }
+            if state.connection.get() == ConnectionState::Playing {
+                state.tab_list.tick();
+            }
+
+        }
+        Event::Chat(msg) => {
+            state.chat.send(msg.message().to_ansi()).await;
+        },
+        Event::Init => {
+            // the user may have asked to disconnect while connecting, done on the next tick
+            if !state.connection.set_if(|current| *current == ConnectionState::Connecting, ConnectionState::LoggingIn) {
+                return Ok(())
+            }
+            let green = Ansi::rgb(ChatFormatting::Green.color().unwrap());
+            state.chat.send(format!("{green}Successfully connected to server.")).await;
+            state.chat.send(format!("{green}Run '.list' for a list of players on the current server.")).await;
+        }
+        Event::Spawn => {
+            state.connection.set(ConnectionState::Playing);
+        }
+        Event::AddPlayer(info) => {
+            let player = TabEntry::from(&info);
+            let joined = state.tab_list.add(player.clone()).then_some(player);
+            if let (Some(player), Some(publisher)) = (joined, &state.publisher) {
+                publisher.publish(Payload::PlayerJoin { player }).await;
+            }
+        }
+        Event::UpdatePlayer(info) => {
+            state.tab_list.update(TabEntry::from(&info));
+        }
+        Event::RemovePlayer(info) => {
+            if let (Some(player), Some(publisher)) = (state.tab_list.remove(&info.uuid), &state.publisher) {
+                publisher.publish(Payload::PlayerLeave { player }).await;
+            }
+        }
+        Event::Disconnect(reason) => {
+            let red = Ansi::rgb(ChatFormatting::Red.color().unwrap());
+            state.chat.send(format!("{red}Disconnected from server: {}",
+                                    reason.clone().unwrap_or(FormattedText::from("No reason provided."))))
+                .await;
+            let reason = match (state.connection.get(), reason) {
+                (ConnectionState::Disconnecting, _) => DisconnectReason::User,
+                // not in game yet, the server refused the login
+                (ConnectionState::Connecting | ConnectionState::LoggingIn, reason) => {
+                    state.connection.fail(match reason {
+                        Some(reason) => ConnectionError::kicked_during_login(&reason.to_string()),
+                        None => ConnectionError::Network("Connection closed while logging in".to_string())
+                    });
+                    return Ok(())
+                }
+                (_, Some(reason)) => DisconnectReason::Kicked(reason.to_string()),
+                (_, None) => DisconnectReason::Lost,
+            };
+            // a kill may have already recorded the disconnection
+            state.connection.set_if(
+                |current| !matches!(current, ConnectionState::Disconnected { .. }),
+                ConnectionState::Disconnected { reason }
+            );
+        }
+        Event::Packet(packet) => {
+            let packet = packet.clone();
+            match packet.deref() {
+                ClientboundGamePacket::SetExperience(packet) => {
+                    state.telemetry.set_experience(Experience {
+                        level: packet.experience_level as u32,
+                        progress: packet.experience_progress,
+                        total: packet.total_experience as u32
+                    });
+                }
+                ClientboundGamePacket::TabList(packet) => {
+                    state.tab_list.set_header_footer(packet.header.to_string(), packet.footer.to_string());
+                }
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+impl ClientInstance {
+    pub fn new(id: Uuid, username: String, uuid: &Uuid,
+               auth: Arc<AuthProtocol>, server: Server,
+               version: Option<Version>, events: EventSender) -> Self {
+        let publisher = Publisher::new(id, events);
+        Self {
+            id,
+            account: create_azalea_account(&auth),
+            info: Info {
+                username,
+                uuid: uuid.to_string(),
+                auth
+            },
+            version: version.unwrap_or_default(),
+            handle: None,
+            client: Arc::new(Mutex::new(None)),
+            target: server,
+            reconnect: ReconnectPolicy::default(),
+            script: None,
+            proxy: None,
+            commands: CommandDispatcher::default(),
+            state: StateHandle::new(publisher.clone()),
+            chat: ChatBuffer::new(publisher.clone()),
+            chat_inputs: Arc::new(Mutex::new(VecDeque::new())),
+            movement_inputs: Arc::new(Mutex::new(VecDeque::new())),
+            telemetry: TelemetryHandle::default(),
+            tab_list: TabListHandle::default(),
+            publisher,
+            client_thread: None
+        }
+    }
+
+    /// Replaces the credentials of the client, e.g. once its tokens have been refreshed.
+    /// A running session keeps the credentials it joined with.
+    pub fn set_auth(&mut self, auth: Arc<AuthProtocol>) {
+        self.account = create_azalea_account(&auth);
+        self.info.auth = auth;
+    }
+
+    /// Whether the client is connected, or currently connecting, see [`ConnectionState::is_running`]
+    pub fn is_running(&self) -> bool {
+        self.state.is_running()
+    }
+
+    /// The current connection state of the client
+    pub fn state(&self) -> ConnectionState {
+        self.state.get()
+    }
+
+    /// The in-game state of the client, sampled every second while it is playing
+    pub fn telemetry(&self) -> Option<Telemetry> {
+        self.telemetry.get()
+    }
+
+    /// The players listed in the tab list of the server the client is playing on
+    pub fn tab_list(&self) -> TabList {
+        self.tab_list.get()
+    }
+
+    /// The chat lines of the instance, kept across reconnections
+    pub fn chat(&self) -> &ChatBuffer {
+        &self.chat
+    }
+
+    /// The client-side commands of the instance, such as '.list'
+    pub fn commands(&self) -> &CommandDispatcher {
+        &self.commands
+    }
+
+    pub fn commands_mut(&mut self) -> &mut CommandDispatcher {
+        &mut self.commands
+    }
+
+    /// Appends a chat message input. These are consumed by the client thread every tick
+    /// and sent onto the server by the client.
+    ///
+    /// This also handles the execution of instance commands, such as '.list', which are run
+    /// right away and only write to the instance's chat, see [`CommandDispatcher`].
+    ///
+    /// Does not distinguish between chat and server commands.
+    pub fn send_message(&mut self, message: String) {
+        if let Some(message) = commands::dispatch(self, &message) {
+            self.chat_inputs.lock().unwrap().push_back(message);
+        }
+    }
+
+    /// Appends a movement command, applied by the client thread on the next tick. Its progress
+    /// is published as [`Payload::Movement`] events.
+    pub fn send_movement(&mut self, movement: Movement) {
+        self.movement_inputs.lock().unwrap().push_back(movement);
+    }
+
+    /// Connect the client to the specified target server.
+    /// If the client is currently connected, it will abort the current connection thread before
+    /// initiating the requested connection.
+    ///
+    /// The connection state goes through [`ConnectionState::Resolving`] and
+    /// [`ConnectionState::Connecting`] before the client joins the server. Once disconnected,
+    /// the client reconnects according to its [`ReconnectPolicy`], going through
+    /// [`ConnectionState::Reconnecting`] in between.
+    pub fn connect(&mut self) {
+        self.abort_thread();
+        self.state.set(ConnectionState::Resolving);
+
+        let instance_key = self.id;
+        let account = self.account.clone();
+        let target = self.target.clone();
+        let version = self.version.clone();
+        let proxy = self.proxy.clone();
+        let join = self.info.auth.session_join();
+
+        let connection = self.state.clone();
+        let chat = self.chat.clone();
+        let chat_inputs = self.chat_inputs.clone();
+        let movement_inputs = self.movement_inputs.clone();
+        let telemetry = self.telemetry.clone();
+        let tab_list = self.tab_list.clone();
+        let publisher = self.publisher.clone();
+        let policy = self.reconnect.clone();
+        let script = self.script.clone();
+
+        self.client_thread = Some(tokio::spawn(async move {
+            let mut attempt = 0;
+            loop {
+                // movement commands don't carry over to a new session
+                movement_inputs.lock().unwrap().clear();
+                let state = ClientState {
+                    instance_key,
+                    connection: connection.clone(),
+                    script: script.clone().map(|path| Arc::new(ScriptHost::new(path, chat.clone()))),
+                    chat: chat.clone(),
+                    chat_inputs: chat_inputs.clone(),
+                    movement_inputs: movement_inputs.clone(),
+                    navigation: Arc::default(),
+                    telemetry: telemetry.clone(),
+                    tab_list: tab_list.clone(),
+                    publisher: Some(publisher.clone())
+                };
+                run_session(account.clone(), target.clone(), version.clone(), proxy.clone(), join.clone(), state).await;
+                telemetry.clear();
+                tab_list.clear();
+
+                let outcome = connection.get();
+                if let ConnectionState::Failed { error } = &outcome {
+                    chat.send(format!("{}{error}", Ansi::rgb(ChatFormatting::Red.color().unwrap()))).await;
+                }
+                // only sessions that joined the game end up disconnected, which resets the backoff
+                if matches!(outcome, ConnectionState::Disconnected { .. }) {
+                    attempt = 0;
+                }
+                if !policy.applies_to(&outcome) {
+                    break
+                }
+                attempt += 1;
+                let Some(delay) = policy.delay(attempt) else {
+                    chat.send(format!("{}Giving up reconnecting after {} attempt(s).",
+                                      Ansi::rgb(ChatFormatting::Red.color().unwrap()), attempt - 1)).await;
+                    break
+                };
+                connection.set(ConnectionState::Reconnecting { attempt, delay_secs: delay.as_secs() });
+                chat.send(format!("{}Reconnecting in {}s (attempt {attempt})...",
+                                  Ansi::rgb(ChatFormatting::Yellow.color().unwrap()), delay.as_secs())).await;
+                wait_reconnect(&connection, delay).await;
+
+                // the user may have asked to disconnect while waiting
+                let resumed = connection.set_if(
+                    |current| matches!(current, ConnectionState::Reconnecting { .. }),
+                    ConnectionState::Resolving
+                );
+                if !resumed {
+                    connection.set_if(
+                        |current| *current == ConnectionState::Disconnecting,
+                        ConnectionState::Disconnected { reason: DisconnectReason::User }
+                    );
+                    break
+                }
+            }
+        }));
+    }
+
+    /// Notifies the client to disconnect, which will then happen on the next tick.
+    ///
+    /// Alternative for [`Self::disconnect`]
+    pub fn disconnect_notify(&mut self) -> Result<(), InstanceEndError> {
+        if !self.state.is_running() {
+            return Err(InstanceEndError::NoConnect(StateSource::Client))
+        }
+        self.state.set(ConnectionState::Disconnecting);
+        Ok(())
+    }
+
+    /// Directly disconnects from Azalea's client handle.
+    ///
+    /// TODO, use [`Self::disconnect_notify`]
+    pub fn disconnect(&mut self) -> Result<(), InstanceEndError> {
+        {
+            let mut guard = self.client.lock().unwrap();
+            if let Some(client) = guard.take() {
+                client.disconnect();
+                Ok(())
+            } else {
+                Err(InstanceEndError::NoConnect(StateSource::Handle))
+            }
+        }
+    }
+
+    /// Directly kills the running client thread, if present.
+    /// Once the thread has aborted, the client state is also notified of this change.
+    ///
+    /// Use is discouraged unless necessary.
+    pub fn kill(&mut self) -> Result<(), InstanceEndError> {
+        if self.abort_thread() {
+            self.state.set(ConnectionState::Disconnected { reason: DisconnectReason::Killed });
+            Ok(())
+        } else {
+            Err(InstanceEndError::NoConnect(StateSource::Thread))
+        }
+    }
+
+    /// Aborts the client thread without touching the connection state.
+    /// Returns whether a thread was present.
+    fn abort_thread(&mut self) -> bool {
+        if let Some(handle) = self.client_thread.take() {
+            handle.abort();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Drop for ClientInstance {
+    fn drop(&mut self) {
+        match self.kill() {
+            Ok(_) => {}
+            Err(err) => {
+                match err {
+                    InstanceEndError::NoConnect(_) => {},
+                    _ => warn!("{}", format!("Failed to kill client connection during drop: {err}"))
+                }
+            }
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,232 @@
+use uuid::Uuid;
+use std::{
+    collections::HashMap,
+    sync::Arc
+};
+use std::path::PathBuf;
+use azalea_auth::{
+    AccessTokenResponse,
+    cache::ExpiringValue
+};
+use log::info;
+use crate::{
+    api::{
+        auth::MinecraftAuthCache,
+        {Server, ApiContext, ClientConnection, ClientworksError}
+    },
+    client::{
+        auth::MinecraftProfile,
+        hooks::EventSender,
+        proxy::Proxy,
+        yggdrasil::{SessionJoin, YggdrasilServer}
+    },
+};
+
+pub mod auth;
+pub mod commands;
+pub mod network;
+pub mod ping;
+pub mod proxy;
+pub mod relay;
+mod instance;
+pub mod hooks;
+pub mod movement;
+pub mod scripting;
+pub mod tablist;
+pub mod telemetry;
+pub mod version;
+pub mod yggdrasil;
+
+#[allow(unused)]
+pub use instance::{
+    ClientInstance,
+    ClientState,
+    Info,
+    InstanceEndError,
+    soft_kill
+};
+pub use version::{Version, AUTO_VERSION};
+
+pub enum AuthProtocol {
+    Offline(String),
+    // token, msa, profile
+    Microsoft(String, Box<ExpiringValue<AccessTokenResponse>>, Box<MinecraftProfile>),
+    // token, auth server, profile
+    Yggdrasil(String, Box<YggdrasilServer>, Box<MinecraftProfile>)
+}
+
+impl AuthProtocol {
+    /// The session server to join before joining online-mode servers, for accounts azalea
+    /// can't join it for.
+    pub fn session_join(&self) -> Option<SessionJoin> {
+        match self {
+            AuthProtocol::Yggdrasil(token, server, profile) => Some(SessionJoin {
+                server: *server.clone(),
+                access_token: token.clone(),
+                uuid: profile.uuid
+            }),
+            _ => None
+        }
+    }
+}
+
+pub struct ControllerContainer {
+    pub list: HashMap<Uuid, ClientController>,
+}
+
+impl Default for ControllerContainer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ControllerContainer {
+    pub fn new() -> Self {
+        Self {
+            list: HashMap::new(),
+        }
+    }
+    
+    pub fn add(&mut self, controller: ClientController) {
+        info!("Registered controller for {}", controller.id);
+        self.list.insert(controller.id, controller);
+    }
+    
+    pub fn contains(&self, uuid: &Uuid) -> bool {
+        self.list.contains_key(uuid)
+    }
+    
+    pub fn remove(&mut self, uuid: &Uuid) {
+        self.list.remove(uuid);
+    }
+    
+    pub fn get(&self, uuid: &Uuid) -> Option<&ClientController> {
+        self.list.get(uuid)
+    }
+    
+    pub fn get_mut(&mut self, uuid: &Uuid) -> Option<&mut ClientController> {
+        self.list.get_mut(uuid)
+    }
+}
+
+pub struct ClientController {
+    pub id: Uuid,
+    pub username: String,
+    pub uuid: Uuid,
+    pub auth: Arc<AuthProtocol>,
+    pub instances: HashMap<Uuid, ClientInstance>,
+    pub scripts_location: PathBuf,
+    /// The proxy of instances which don't have their own, see [`ClientInstance::proxy`]
+    pub proxy: Option<Proxy>,
+    events: EventSender
+}
+
+impl ClientController {
+    /// Creates a controller without any instance. The scripts of its instances are loaded from
+    /// `scripts_location`, see [`ApiContext::scripts_dir`].
+    pub fn new(id: Uuid, username: String, uuid: Uuid, auth: Arc<AuthProtocol>,
+               scripts_location: PathBuf, events: EventSender) -> Self {
+        Self {
+            id,
+            username,
+            uuid,
+            auth,
+            instances: HashMap::new(),
+            scripts_location,
+            proxy: None,
+            events
+        }
+    }
+
+    pub fn new_cached(api: &mut ApiContext, client_id: &Uuid, auth_cache: &MinecraftAuthCache)
+        -> Result<Self, ClientworksError> {
+        let client = api.clients.get_by_id(client_id)
+            .ok_or_else(|| ClientworksError::ClientNotFound { id: client_id.to_string() })?;
+        let profile = &auth_cache.profile;
+        let mut controller = {
+            ClientController::new(
+                *client_id, profile.username.clone(), profile.uuid,
+                Arc::new(auth_cache.protocol()),
+                api.scripts_dir(), api.events.clone()
+            )
+        };
+        controller.proxy = client.proxy.clone();
+        for (key, connection) in client.connections.iter() {
+            controller.restore_instance(connection);
+        }
+
+        Ok(controller)
+    }
+
+    /// Creates a controller for an offline client, restoring the instances of all of its
+    /// registered connections.
+    pub fn new_offline(api: &mut ApiContext, client_id: &Uuid, profile: &MinecraftProfile)
+        -> Result<Self, ClientworksError> {
+        let client = api.clients.get_by_id(client_id)
+            .ok_or_else(|| ClientworksError::ClientNotFound { id: client_id.to_string() })?;
+        let mut controller = {
+            ClientController::new(
+                *client_id, profile.username.clone(), profile.uuid,
+                Arc::new(AuthProtocol::Offline(profile.username.clone())),
+                api.scripts_dir(), api.events.clone()
+            )
+        };
+        controller.proxy = client.proxy.clone();
+        for (key, connection) in client.connections.iter() {
+            controller.restore_instance(connection);
+        }
+
+        Ok(controller)
+    }
+
+    /// Recreates the instance of a connection registered in the client list.
+    fn restore_instance(&mut self, connection: &ClientConnection) {
+        let mut instance = ClientInstance::new(
+            connection.id, self.username.clone(), &self.uuid, self.auth.clone(),
+            connection.server.clone(), Some(connection.version.clone()),
+            self.events.clone()
+        );
+        instance.reconnect = connection.reconnect.clone();
+        instance.script = connection.script.as_ref().map(|name| self.scripts_location.join(name));
+        instance.proxy = connection.proxy.clone().or_else(|| self.proxy.clone());
+        if let Some(prefix) = &connection.command_prefix {
+            instance.commands_mut().set_prefix(prefix.clone());
+        }
+        self.instances.insert(connection.id, instance);
+    }
+
+    /// Creates an instance going through the client's proxy, if any.
+    pub fn create_instance(&mut self, server: Server, version: Option<Version>) -> Uuid {
+        let id = Uuid::new_v4();
+        let mut instance = {
+            ClientInstance::new(id, self.username.clone(), &self.uuid,
+                                self.auth.clone(), server, version,
+                                self.events.clone()
+            )
+        };
+        instance.proxy = self.proxy.clone();
+        self.instances.insert(id, instance);
+        id
+    }
+
+    /// Replaces the credentials of the client and of all of its instances, see
+    /// [`ClientInstance::set_auth`].
+    pub fn set_auth(&mut self, auth: Arc<AuthProtocol>) {
+        for instance in self.instances.values_mut() {
+            instance.set_auth(auth.clone());
+        }
+        self.auth = auth;
+    }
+
+    pub fn get_instance(&self, uuid: &Uuid) -> Option<&ClientInstance> {
+        self.instances.get(uuid)
+    }
+
+    pub fn get_instance_mut(&mut self, uuid: &Uuid) -> Option<&mut ClientInstance> {
+        self.instances.get_mut(uuid)
+    }
+
+    pub fn remove_instance(&mut self, uuid: &Uuid) {
+        self.instances.remove(uuid);
+    }
+}
@@ -0,0 +1,189 @@
+use std::fmt;
+use azalea::{
+    ecs::query::With,
+    entity::{metadata::Player, Position},
+    pathfinder::goals::{BlockPosGoal, Goal, RadiusGoal},
+    prelude::*,
+    BlockPos, GameProfileComponent, SprintDirection, Vec3, WalkDirection
+};
+use serde::{Deserialize, Serialize};
+
+/// How often the position of a followed player is checked, in ticks.
+const FOLLOW_INTERVAL_TICKS: u32 = 20;
+
+/// How close to a followed player the client stays, in blocks.
+const FOLLOW_DISTANCE: f32 = 2.0;
+
+/// How many ticks the pathfinder is given to start calculating a path, before its goal
+/// being cleared is taken as the end of the path.
+const PATHFINDER_WARMUP_TICKS: u32 = 5;
+
+/// A movement command sent to an instance, applied on its next tick,
+/// e.g. `{ "action": "Goto", "x": 12, "y": 64, "z": -30 }`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "action")]
+pub enum Movement {
+    /// Walks to the given block, finding a path with azalea's pathfinder.
+    Goto { x: i32, y: i32, z: i32 },
+    /// Keeps walking up to the given player, as long as they are in render distance.
+    Follow { player: String },
+    /// Stops walking, pathfinding and following.
+    Stop,
+    Jump,
+    Sneak { enabled: bool },
+    /// Sprints straight ahead, or stops.
+    Sprint { enabled: bool },
+}
+
+/// The progress of the movement of an instance, published as a
+/// [`Payload::Movement`](crate::client::hooks::Payload::Movement).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "status")]
+pub enum MovementStatus {
+    /// Started walking to `goal`.
+    Moving { goal: String },
+    Reached { goal: String },
+    /// The pathfinder gave up on `goal`, e.g. because no path could be found.
+    Failed { goal: String, reason: String },
+    Following { player: String },
+    /// The followed player is out of render distance, or has left.
+    TargetLost { player: String },
+    Stopped,
+}
+
+impl fmt::Display for MovementStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MovementStatus::Moving { goal } => write!(f, "moving to {goal}"),
+            MovementStatus::Reached { goal } => write!(f, "reached {goal}"),
+            MovementStatus::Failed { goal, reason } => write!(f, "failed to reach {goal}: {reason}"),
+            MovementStatus::Following { player } => write!(f, "following {player}"),
+            MovementStatus::TargetLost { player } => write!(f, "lost track of {player}"),
+            MovementStatus::Stopped => write!(f, "stopped"),
+        }
+    }
+}
+
+enum Target {
+    Block(BlockPos),
+    Player { name: String, last: Option<Vec3> },
+}
+
+/// The movement goal of a client during a session, updated every tick.
+#[derive(Default)]
+pub struct Navigation {
+    target: Option<Target>,
+    /// Ticks since the current target was set
+    ticks: u32,
+}
+
+fn describe(pos: &BlockPos) -> String {
+    format!("{} {} {}", pos.x, pos.y, pos.z)
+}
+
+/// The position of a player in render distance, by username.
+fn find_player(client: &Client, name: &str) -> Option<Vec3> {
+    let mut ecs = client.ecs.lock();
+    let mut query = ecs.query_filtered::<(&GameProfileComponent, &Position), With<Player>>();
+    query.iter(&ecs)
+        .find(|(profile, _)| profile.name == name)
+        .map(|(_, position)| **position)
+}
+
+impl Navigation {
+    fn set_target(&mut self, target: Option<Target>) {
+        self.target = target;
+        self.ticks = 0;
+    }
+
+    /// Applies a movement command, returning the status to publish if it changed.
+    pub fn apply(&mut self, client: &Client, movement: Movement) -> Option<MovementStatus> {
+        match movement {
+            Movement::Goto { x, y, z } => {
+                let pos = BlockPos::new(x, y, z);
+                client.start_goto(BlockPosGoal(pos));
+                self.set_target(Some(Target::Block(pos)));
+                Some(MovementStatus::Moving { goal: describe(&pos) })
+            }
+            Movement::Follow { player } => {
+                self.set_target(Some(Target::Player { name: player.clone(), last: None }));
+                Some(MovementStatus::Following { player })
+            }
+            Movement::Stop => {
+                client.stop_pathfinding();
+                client.walk(WalkDirection::None);
+                self.set_target(None);
+                Some(MovementStatus::Stopped)
+            }
+            Movement::Jump => {
+                client.jump();
+                None
+            }
+            Movement::Sneak { enabled } => {
+                client.set_crouching(enabled);
+                None
+            }
+            Movement::Sprint { enabled } => {
+                if enabled {
+                    client.sprint(SprintDirection::Forward);
+                } else {
+                    client.walk(WalkDirection::None);
+                }
+                None
+            }
+        }
+    }
+
+    /// Checks on the current target, returning the status to publish if it changed.
+    pub fn tick(&mut self, client: &Client) -> Option<MovementStatus> {
+        self.ticks += 1;
+        match self.target.as_mut()? {
+            Target::Block(pos) => {
+                let pos = *pos;
+                if self.ticks < PATHFINDER_WARMUP_TICKS || !client.is_goto_target_reached() {
+                    return None
+                }
+                self.set_target(None);
+                let goal = describe(&pos);
+                if BlockPosGoal(pos).success(BlockPos::from(client.position())) {
+                    Some(MovementStatus::Reached { goal })
+                } else {
+                    Some(MovementStatus::Failed { goal, reason: "No path found".to_string() })
+                }
+            }
+            Target::Player { name, last } => {
+                if self.ticks % FOLLOW_INTERVAL_TICKS != 1 {
+                    return None
+                }
+                let Some(position) = find_player(client, name) else {
+                    let player = name.clone();
+                    client.stop_pathfinding();
+                    self.set_target(None);
+                    return Some(MovementStatus::TargetLost { player })
+                };
+                // only recalculate the path once the player has moved away
+                if last.is_none_or(|last| last.distance_to(&position) > FOLLOW_DISTANCE as f64) {
+                    client.start_goto(RadiusGoal { pos: position, radius: FOLLOW_DISTANCE });
+                    *last = Some(position);
+                }
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::movement::{Movement, MovementStatus};
+
+    #[test]
+    fn movement_format() {
+        let movement: Movement = serde_json::from_str(r#"{ "action": "Goto", "x": 12, "y": 64, "z": -30 }"#).unwrap();
+        assert_eq!(movement, Movement::Goto { x: 12, y: 64, z: -30 });
+        let status = MovementStatus::Failed { goal: "12 64 -30".to_string(), reason: "No path found".to_string() };
+        assert_eq!(
+            serde_json::to_string(&status).unwrap(),
+            r#"{"status":"Failed","goal":"12 64 -30","reason":"No path found"}"#
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration
+};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::{
+    api::Server,
+    client::hooks::{Payload, Publisher}
+};
+
+/// Why an instance got disconnected.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", content = "message")]
+pub enum DisconnectReason {
+    /// The user requested the disconnection.
+    User,
+    /// The client thread was killed.
+    Killed,
+    /// The server kicked the client, with the given reason.
+    Kicked(String),
+    /// The connection was lost without the server providing a reason.
+    Lost,
+}
+
+impl fmt::Display for DisconnectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisconnectReason::User => write!(f, "Disconnected by user"),
+            DisconnectReason::Killed => write!(f, "Client thread killed"),
+            DisconnectReason::Kicked(reason) => write!(f, "Kicked: {reason}"),
+            DisconnectReason::Lost => write!(f, "Connection lost"),
+        }
+    }
+}
+
+/// Why a connection attempt failed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", content = "message")]
+pub enum ConnectionError {
+    /// The server could not be reached: address resolution, refused connection, timeout...
+    Network(String),
+    /// The session or the account's credentials were rejected.
+    Auth(String),
+    /// The server doesn't support the version of the client.
+    VersionMismatch(String),
+    /// The server kicked the client before it joined the game.
+    KickedDuringLogin(String),
+    /// Anything else, including panics of the client thread.
+    Other(String),
+}
+
+impl ConnectionError {
+    // Auth and version errors are never retried, so only the exact phrases of vanilla servers,
+    // common proxies and azalea qualify: any kick message may mention a "session" or a "version".
+    const VERSION_HINTS: [&'static str; 6] = [
+        "outdated client", "outdated server", "incompatible client", "unsupported client version",
+        "unsupported protocol version", "protocol mismatch"
+    ];
+    const AUTH_HINTS: [&'static str; 6] = [
+        "failed to verify username", "invalid session", "session is invalid", "invalid access token",
+        "forbidden operation", "multiplayer is disabled"
+    ];
+    const NETWORK_HINTS: [&'static str; 10] = [
+        "connection refused", "timed out", "failed to resolve", "dns error", "network is unreachable",
+        "host is unreachable", "connection reset", "broken pipe", "io error", "unexpected end of file"
+    ];
+
+    /// Classifies an error from its message.
+    pub fn classify(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let matches = |hints: &[&str]| hints.iter().any(|hint| lower.contains(hint));
+        if matches(&Self::VERSION_HINTS) {
+            Self::VersionMismatch(message.to_string())
+        } else if matches(&Self::AUTH_HINTS) {
+            Self::Auth(message.to_string())
+        } else if matches(&Self::NETWORK_HINTS) {
+            Self::Network(message.to_string())
+        } else {
+            Self::Other(message.to_string())
+        }
+    }
+
+    /// Classifies the reason of a kick received before the client joined the game.
+    pub fn kicked_during_login(reason: &str) -> Self {
+        match Self::classify(reason) {
+            Self::Other(reason) | Self::Network(reason) => Self::KickedDuringLogin(reason),
+            error => error
+        }
+    }
+}
+
+impl fmt::Display for ConnectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionError::Network(msg) => write!(f, "Network error: {msg}"),
+            ConnectionError::Auth(msg) => write!(f, "Authentication error: {msg}"),
+            ConnectionError::VersionMismatch(msg) => write!(f, "Version mismatch: {msg}"),
+            ConnectionError::KickedDuringLogin(msg) => write!(f, "Kicked during login: {msg}"),
+            ConnectionError::Other(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+/// The connection state of a client instance.
+///
+/// An instance starts [`Idle`](ConnectionState::Idle), and goes through `Resolving`,
+/// `Connecting`, `LoggingIn` and `Playing` once connected. It ends up either
+/// [`Disconnected`](ConnectionState::Disconnected) or [`Failed`](ConnectionState::Failed).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(tag = "state")]
+pub enum ConnectionState {
+    #[default]
+    Idle,
+    Resolving,
+    Connecting,
+    LoggingIn,
+    Playing,
+    Disconnecting,
+    Disconnected { reason: DisconnectReason },
+    Failed { error: ConnectionError },
+    /// Waiting to reconnect after a disconnection or failure, see [`ReconnectPolicy`].
+    Reconnecting { attempt: u32, delay_secs: u64 },
+}
+
+impl ConnectionState {
+    /// Whether the client is connected to the server, or is trying to.
+    pub fn is_running(&self) -> bool {
+        matches!(self,
+            ConnectionState::Resolving | ConnectionState::Connecting
+            | ConnectionState::LoggingIn | ConnectionState::Playing
+            | ConnectionState::Reconnecting { .. }
+        )
+    }
+
+    /// Whether the client is on its way to the server, but isn't playing yet.
+    pub fn is_joining(&self) -> bool {
+        matches!(self,
+            ConnectionState::Resolving | ConnectionState::Connecting | ConnectionState::LoggingIn
+        )
+    }
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionState::Idle => write!(f, "idle"),
+            ConnectionState::Resolving => write!(f, "resolving"),
+            ConnectionState::Connecting => write!(f, "connecting"),
+            ConnectionState::LoggingIn => write!(f, "logging in"),
+            ConnectionState::Playing => write!(f, "playing"),
+            ConnectionState::Disconnecting => write!(f, "disconnecting"),
+            ConnectionState::Disconnected { reason } => write!(f, "disconnected ({reason})"),
+            ConnectionState::Failed { error } => write!(f, "failed ({error})"),
+            ConnectionState::Reconnecting { attempt, delay_secs } => {
+                write!(f, "reconnecting in {delay_secs}s (attempt {attempt})")
+            }
+        }
+    }
+}
+
+/// Whether and how an instance reconnects after it got disconnected or failed to connect.
+///
+/// Stored on each connection in `clients.json`, e.g. `{ "mode": "Backoff", "initial_secs": 5,
+/// "max_secs": 300, "max_attempts": 10 }`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(tag = "mode")]
+pub enum ReconnectPolicy {
+    #[default]
+    Off,
+    /// Waits the same delay before every attempt. Retries forever if `max_attempts` is `None`.
+    Fixed { delay_secs: u64, max_attempts: Option<u32> },
+    /// Doubles the delay after every attempt, starting from `initial_secs` and capped at `max_secs`.
+    Backoff { initial_secs: u64, max_secs: u64, max_attempts: u32 },
+}
+
+impl ReconnectPolicy {
+    /// Kick messages of bans and whitelists, after which reconnecting is pointless.
+    const REFUSAL_HINTS: [&'static str; 4] = [
+        "banned", "whitelist", "white-list", "white listed"
+    ];
+
+    /// The delay before the given reconnection attempt (starting from 1), or `None` if the
+    /// policy has given up.
+    pub fn delay(&self, attempt: u32) -> Option<Duration> {
+        match self {
+            ReconnectPolicy::Off => None,
+            ReconnectPolicy::Fixed { delay_secs, max_attempts } => {
+                if max_attempts.is_some_and(|max| attempt > max) {
+                    return None
+                }
+                Some(Duration::from_secs(*delay_secs))
+            }
+            ReconnectPolicy::Backoff { initial_secs, max_secs, max_attempts } => {
+                if attempt > *max_attempts {
+                    return None
+                }
+                let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+                Some(Duration::from_secs(initial_secs.saturating_mul(factor).min(*max_secs)))
+            }
+        }
+    }
+
+    /// Whether an instance that ended up in the given state should reconnect at all.
+    ///
+    /// Disconnections requested by the user, bans, whitelists, rejected credentials and
+    /// version mismatches are never retried.
+    pub fn applies_to(&self, state: &ConnectionState) -> bool {
+        let refused = |message: &str| {
+            let lower = message.to_lowercase();
+            Self::REFUSAL_HINTS.iter().any(|hint| lower.contains(hint))
+        };
+        match state {
+            _ if *self == ReconnectPolicy::Off => false,
+            ConnectionState::Disconnected { reason } => match reason {
+                DisconnectReason::Kicked(message) => !refused(message),
+                DisconnectReason::Lost => true,
+                DisconnectReason::User | DisconnectReason::Killed => false,
+            },
+            ConnectionState::Failed { error } => match error {
+                ConnectionError::KickedDuringLogin(message) => !refused(message),
+                ConnectionError::Network(_) | ConnectionError::Other(_) => true,
+                ConnectionError::Auth(_) | ConnectionError::VersionMismatch(_) => false,
+            },
+            _ => false
+        }
+    }
+}
+
+/// A shared handle to the [`ConnectionState`] of an instance, which publishes every
+/// transition to the event channel as a [`Payload::State`].
+#[derive(Clone, Default)]
+pub struct StateHandle {
+    key: Uuid,
+    state: Arc<Mutex<ConnectionState>>,
+    publisher: Option<Publisher>,
+}
+
+impl StateHandle {
+    pub fn new(publisher: Publisher) -> Self {
+        Self {
+            key: publisher.key(),
+            state: Arc::new(Mutex::new(ConnectionState::Idle)),
+            publisher: Some(publisher),
+        }
+    }
+
+    pub fn get(&self) -> ConnectionState {
+        self.state.lock().unwrap().clone()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state.lock().unwrap().is_running()
+    }
+
+    /// Transitions to `state`, notifying the event channel if it differs from the current one.
+    pub fn set(&self, state: ConnectionState) {
+        {
+            let mut guard = self.state.lock().unwrap();
+            if *guard == state {
+                return
+            }
+            *guard = state.clone();
+        }
+        self.publish(Payload::State { state });
+    }
+
+    /// Transitions to [`ConnectionState::Failed`], whose event carries the error.
+    pub fn fail(&self, error: ConnectionError) {
+        warn!("Instance {} failed to connect: {error}", self.key);
+        self.set(ConnectionState::Failed { error });
+    }
+
+    fn publish(&self, payload: Payload) {
+        if let Some(publisher) = &self.publisher {
+            publisher.publish_state(payload);
+        }
+    }
+
+    /// Transitions to `state` only if the current state satisfies `condition`, checked under
+    /// the same lock as the transition so that no other transition can land in between.
+    pub fn set_if(&self, condition: impl FnOnce(&ConnectionState) -> bool, state: ConnectionState) -> bool {
+        {
+            let mut guard = self.state.lock().unwrap();
+            if !condition(&guard) {
+                return false
+            }
+            if *guard == state {
+                return true
+            }
+            *guard = state.clone();
+        }
+        self.publish(Payload::State { state });
+        true
+    }
+}
+
+pub struct ConnectionHandle {
+    pub server: Server,
+    pub status: ConnectionState,
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::client::network::{ConnectionError, ConnectionState, DisconnectReason, ReconnectPolicy};
+
+    #[test]
+    fn error_classification() {
+        assert_eq!(
+            ConnectionError::classify("Connection refused (os error 111)"),
+            ConnectionError::Network("Connection refused (os error 111)".to_string())
+        );
+        assert!(matches!(ConnectionError::classify("Outdated server! I'm still on 1.20.4"),
+                         ConnectionError::VersionMismatch(_)));
+        assert!(matches!(ConnectionError::classify("Failed to verify username!"),
+                         ConnectionError::Auth(_)));
+        assert!(matches!(ConnectionError::kicked_during_login("You are not whitelisted on this server!"),
+                         ConnectionError::KickedDuringLogin(_)));
+        // ordinary kicks mentioning a session or a version are retried
+        assert!(matches!(ConnectionError::kicked_during_login("Your session has ended, please reconnect"),
+                         ConnectionError::KickedDuringLogin(_)));
+        assert!(matches!(ConnectionError::kicked_during_login("Restarting for the new server version"),
+                         ConnectionError::KickedDuringLogin(_)));
+    }
+
+    #[test]
+    fn reconnect_backoff() {
+        let policy = ReconnectPolicy::Backoff { initial_secs: 5, max_secs: 60, max_attempts: 6 };
+        let delays: Vec<_> = (1..=7).map(|attempt| policy.delay(attempt)).collect();
+        assert_eq!(delays, [5, 10, 20, 40, 60, 60].map(|secs| Some(Duration::from_secs(secs)))
+            .into_iter().chain([None]).collect::<Vec<_>>());
+
+        let kicked = |reason: &str| ConnectionState::Disconnected {
+            reason: DisconnectReason::Kicked(reason.to_string())
+        };
+        assert!(policy.applies_to(&kicked("Server closed")));
+        assert!(!policy.applies_to(&kicked("You are banned from this server.")));
+        assert!(!policy.applies_to(&ConnectionState::Failed {
+            error: ConnectionError::KickedDuringLogin("You are not whitelisted on this server!".to_string())
+        }));
+        assert!(!ReconnectPolicy::Off.applies_to(&kicked("Server closed")));
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    io,
+    time::{Duration, Instant}
+};
+use azalea::protocol::ServerAddress;
+use azalea_chat::FormattedText;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream
+};
+use crate::api::Server;
+
+/// How long a server may take to answer a status query, from resolving its address to the pong.
+pub const STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The protocol version sent in the handshake; by convention, `-1` when the client is only
+/// querying the status to find out the version of the server.
+const STATUS_PROTOCOL: i32 = -1;
+
+/// The largest packet accepted from the server. Status responses with a favicon are usually
+/// a few dozen kilobytes.
+const MAX_PACKET_SIZE: usize = 2 * 1024 * 1024;
+
+/// A player of the sample sent in a status response; not every server sends one.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SamplePlayer {
+    pub name: String,
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Players {
+    pub online: i32,
+    pub max: i32,
+    #[serde(default)]
+    pub sample: Vec<SamplePlayer>,
+}
+
+/// The status of a server, as answered to a Server List Ping.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ServerStatus {
+    /// The MOTD without its formatting
+    pub motd: String,
+    /// The MOTD with ANSI formatting codes, like chat lines
+    pub motd_ansi: String,
+    /// e.g. `Paper 1.21.4`
+    pub version: String,
+    pub protocol: i32,
+    pub players: Players,
+    /// The server icon as a `data:image/png;base64,...` URI
+    pub favicon: Option<String>,
+    /// Round-trip time of the ping, in milliseconds
+    pub latency: u64,
+}
+
+#[derive(Deserialize)]
+struct VersionResponse {
+    name: String,
+    protocol: i32,
+}
+
+#[derive(Deserialize)]
+struct StatusResponse {
+    version: Option<VersionResponse>,
+    #[serde(default)]
+    players: Players,
+    #[serde(default)]
+    description: serde_json::Value,
+    favicon: Option<String>,
+}
+
+pub(crate) fn write_varint(buf: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7F == 0 {
+            buf.push(value as u8);
+            return
+        }
+        buf.push((value & 0x7F | 0x80) as u8);
+        value >>= 7;
+    }
+}
+
+pub(crate) async fn read_varint<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<i32> {
+    let mut value = 0u32;
+    for i in 0..5 {
+        let byte = reader.read_u8().await?;
+        value |= ((byte & 0x7F) as u32) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok(value as i32)
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::InvalidData, "VarInt is too long"))
+}
+
+pub(crate) fn write_string(buf: &mut Vec<u8>, value: &str) {
+    write_varint(buf, value.len() as i32);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+pub(crate) async fn read_string<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<String> {
+    let len = read_varint(reader).await?;
+    let mut data = vec![0; usize::try_from(len).map_err(|_| invalid("Negative string length"))?];
+    reader.read_exact(&mut data).await?;
+    String::from_utf8(data).map_err(|_| invalid("String is not valid UTF-8"))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Writes an uncompressed packet: its length, its ID and its data.
+pub(crate) async fn write_packet<W: AsyncWrite + Unpin>(writer: &mut W, id: i32, data: &[u8]) -> io::Result<()> {
+    let mut packet = Vec::with_capacity(data.len() + 1);
+    write_varint(&mut packet, id);
+    packet.extend_from_slice(data);
+    let mut framed = Vec::with_capacity(packet.len() + 3);
+    write_varint(&mut framed, packet.len() as i32);
+    framed.extend_from_slice(&packet);
+    writer.write_all(&framed).await
+}
+
+/// Reads an uncompressed packet, returning its ID and its data.
+pub(crate) async fn read_packet<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<(i32, Vec<u8>)> {
+    let len = usize::try_from(read_varint(reader).await?)
+        .ok()
+        .filter(|len| *len <= MAX_PACKET_SIZE)
+        .ok_or_else(|| invalid("Invalid packet length"))?;
+    let mut data = vec![0; len];
+    reader.read_exact(&mut data).await?;
+    let mut slice = data.as_slice();
+    let id = read_varint(&mut slice).await?;
+    Ok((id, slice.to_vec()))
+}
+
+impl From<StatusResponse> for ServerStatus {
+    fn from(response: StatusResponse) -> Self {
+        let (motd, motd_ansi) = match serde_json::from_value::<FormattedText>(response.description) {
+            Ok(text) => (text.to_string(), text.to_ansi()),
+            Err(_) => (String::new(), String::new())
+        };
+        let (version, protocol) = response.version
+            .map(|version| (version.name, version.protocol))
+            .unwrap_or_default();
+        Self {
+            motd,
+            motd_ansi,
+            version,
+            protocol,
+            players: response.players,
+            favicon: response.favicon,
+            latency: 0
+        }
+    }
+}
+
+async fn query(host: &str, port: u16) -> io::Result<ServerStatus> {
+    let address = ServerAddress { host: host.to_string(), port };
+    let resolved = azalea::protocol::resolver::resolve_address(&address).await
+        .map_err(|e| io::Error::other(format!("Failed to resolve {host}:{port}: {e}")))?;
+    let mut stream = TcpStream::connect(resolved).await?;
+
+    let mut handshake = Vec::new();
+    write_varint(&mut handshake, STATUS_PROTOCOL);
+    write_string(&mut handshake, host);
+    handshake.extend_from_slice(&port.to_be_bytes());
+    write_varint(&mut handshake, 1);
+    write_packet(&mut stream, 0x00, &handshake).await?;
+
+    let start = Instant::now();
+    write_packet(&mut stream, 0x00, &[]).await?;
+    let (id, data) = read_packet(&mut stream).await?;
+    if id != 0x00 {
+        return Err(invalid("Unexpected packet instead of the status response"))
+    }
+    let response: StatusResponse = serde_json::from_str(&read_string(&mut data.as_slice()).await?)
+        .map_err(|e| invalid(&format!("Invalid status response: {e}")))?;
+    let mut status = ServerStatus::from(response);
+    status.latency = start.elapsed().as_millis() as u64;
+
+    // some servers close the connection instead of answering the ping,
+    // the latency of the status response is kept then
+    let payload = chrono::Utc::now().timestamp_millis();
+    let start = Instant::now();
+    let pong = match write_packet(&mut stream, 0x01, &payload.to_be_bytes()).await {
+        Ok(()) => read_packet(&mut stream).await,
+        Err(e) => Err(e)
+    };
+    if let Ok((0x01, _)) = pong {
+        status.latency = start.elapsed().as_millis() as u64;
+    }
+    Ok(status)
+}
+
+/// Queries the status of a server with a Server List Ping, following its SRV record if any.
+pub async fn ping(server: &Server) -> Result<ServerStatus, String> {
+    match tokio::time::timeout(STATUS_TIMEOUT, query(&server.ip, server.port)).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err(format!("Timed out after {}s", STATUS_TIMEOUT.as_secs()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::Server,
+        client::ping::{ping, read_packet, write_packet, write_string},
+        test_support::StandIn
+    };
+
+    const RESPONSE: &str = r#"{
+        "version": { "name": "Paper 1.21.4", "protocol": 769 },
+        "players": { "max": 20, "online": 1, "sample": [{ "name": "Steve", "id": "8667ba71-b85a-4004-af54-457a9734eed7" }] },
+        "description": { "text": "A ", "extra": [{ "text": "fake", "bold": true }, " server"] },
+        "favicon": "data:image/png;base64,AAAA"
+    }"#;
+
+    #[tokio::test]
+    async fn server_list_ping() {
+        let responder = StandIn::once(|mut stream| async move {
+            let (id, handshake) = read_packet(&mut stream).await.unwrap();
+            assert_eq!((id, handshake.last()), (0x00, Some(&1)));
+            let (id, request) = read_packet(&mut stream).await.unwrap();
+            assert_eq!((id, request.len()), (0x00, 0));
+            let mut response = Vec::new();
+            write_string(&mut response, RESPONSE);
+            write_packet(&mut stream, 0x00, &response).await.unwrap();
+            let (id, payload) = read_packet(&mut stream).await.unwrap();
+            assert_eq!(id, 0x01);
+            write_packet(&mut stream, 0x01, &payload).await.unwrap();
+        }).await;
+
+        let port = responder.addr().port();
+        let status = ping(&Server::new("fake".to_string(), "127.0.0.1".to_string(), port)).await.unwrap();
+        responder.finish().await;
+        assert_eq!(status.motd, "A fake server");
+        assert_eq!((status.version.as_str(), status.protocol), ("Paper 1.21.4", 769));
+        assert_eq!((status.players.online, status.players.max), (1, 20));
+        assert_eq!(status.players.sample[0].name, "Steve");
+        assert_eq!(status.favicon.as_deref(), Some("data:image/png;base64,AAAA"));
+
+        // the responder is gone, along with its listener
+        assert!(ping(&Server::new("closed".to_string(), "127.0.0.1".to_string(), port)).await.is_err());
+    }
+}
@@ -0,0 +1,389 @@
+use std::{
+    io,
+    net::SocketAddr,
+    ops::RangeInclusive,
+    sync::{Arc, Mutex}
+};
+use azalea_crypto::{Aes128CfbDec, Aes128CfbEnc};
+use log::{info, warn};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle
+};
+use crate::{
+    api::Server,
+    client::{
+        network::ConnectionError,
+        ping::{read_packet, read_varint, read_string, write_packet, write_string, write_varint},
+        proxy::{self, Proxy},
+        yggdrasil::{self, SessionJoin}
+    }
+};
+
+/// The intents of a handshake leading to the login, rather than to a status query.
+const LOGIN_INTENTS: [i32; 2] = [2, 3];
+
+/// From 1.20.5, servers tell in their encryption request whether the client must join the
+/// session server.
+const SHOULD_AUTHENTICATE_PROTOCOL: i32 = 766;
+
+/// From 1.19 to 1.19.2, the encryption response carries a flag before the verify token.
+const SIGNED_RESPONSE_PROTOCOLS: RangeInclusive<i32> = 759..=760;
+
+/// The handshake sent by the client, with the server address rewritten.
+struct Handshake {
+    packet: Vec<u8>,
+    protocol: i32,
+    intent: i32,
+}
+
+/// Reads the handshake sent by the client, and rewrites the server address it carries, as the
+/// client only knows the address of the relay.
+async fn rewrite_handshake<R: AsyncRead + Unpin>(client: &mut R, target: &Server) -> io::Result<Handshake> {
+    let (id, data) = read_packet(client).await?;
+    let mut packet = Vec::new();
+    let mut slice = data.as_slice();
+    if id != 0x00 {
+        write_packet(&mut packet, id, &data).await?;
+        return Ok(Handshake { packet, protocol: 0, intent: 0 })
+    }
+    let protocol = read_varint(&mut slice).await?;
+    read_string(&mut slice).await?;
+    slice.read_u16().await?;
+    let mut handshake = Vec::new();
+    write_varint(&mut handshake, protocol);
+    write_string(&mut handshake, &target.ip);
+    handshake.extend_from_slice(&target.port.to_be_bytes());
+    handshake.extend_from_slice(slice);
+    write_packet(&mut packet, 0x00, &handshake).await?;
+    Ok(Handshake { packet, protocol, intent: read_varint(&mut slice).await? })
+}
+
+async fn read_bytes<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let len = usize::try_from(read_varint(reader).await?)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Negative array length"))?;
+    let mut data = vec![0; len];
+    reader.read_exact(&mut data).await?;
+    Ok(data)
+}
+
+fn write_bytes(buf: &mut Vec<u8>, data: &[u8]) {
+    write_varint(buf, data.len() as i32);
+    buf.extend_from_slice(data);
+}
+
+/// Answers the encryption request of the server in place of the client, joining the session
+/// server of its Yggdrasil account, which azalea can't do on its own. The client is never asked
+/// to encrypt the connection, so it keeps talking to the relay in plain.
+///
+/// # Returns
+/// The ciphers of the connection to the server, or `None` if the server didn't ask for
+/// encryption, as offline-mode servers do
+///
+/// # Errors
+/// An error of kind [`io::ErrorKind::PermissionDenied`] if the session server rejected the
+/// account.
+async fn login(
+    client: &mut TcpStream,
+    upstream: &mut TcpStream,
+    protocol: i32,
+    join: &SessionJoin,
+    http: &reqwest::Client
+) -> io::Result<Option<(Aes128CfbEnc, Aes128CfbDec)>> {
+    let (id, data) = read_packet(client).await?;
+    write_packet(upstream, id, &data).await?;
+
+    // the server asks for encryption right away, if at all
+    let (id, data) = read_packet(upstream).await?;
+    if id != 0x01 {
+        write_packet(client, id, &data).await?;
+        return Ok(None)
+    }
+    let mut slice = data.as_slice();
+    let server_id = read_string(&mut slice).await?;
+    let public_key = read_bytes(&mut slice).await?;
+    let challenge = read_bytes(&mut slice).await?;
+    let should_authenticate = protocol < SHOULD_AUTHENTICATE_PROTOCOL || slice.read_u8().await? != 0;
+
+    let encrypted = azalea_crypto::encrypt(&public_key, &challenge).map_err(io::Error::other)?;
+    if should_authenticate {
+        let hash = azalea_crypto::hex_digest(&azalea_crypto::digest_data(
+            server_id.as_bytes(), &public_key, &encrypted.secret_key
+        ));
+        yggdrasil::join(http, &join.server, &join.access_token, &join.uuid, &hash).await
+            .map_err(|e| io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("Failed to join the session server: {e}")
+            ))?;
+    }
+    let mut response = Vec::new();
+    write_bytes(&mut response, &encrypted.encrypted_public_key);
+    if SIGNED_RESPONSE_PROTOCOLS.contains(&protocol) {
+        response.push(1);
+    }
+    write_bytes(&mut response, &encrypted.encrypted_challenge);
+    write_packet(upstream, 0x01, &response).await?;
+    Ok(Some(azalea_crypto::create_cipher(&encrypted.secret_key)))
+}
+
+/// Copies everything from `reader` to `writer`, transforming it on the way.
+async fn pipe<R, W, F>(mut reader: R, mut writer: W, mut transform: F) -> io::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+    F: FnMut(&mut [u8]),
+{
+    let mut buf = vec![0; 8192];
+    loop {
+        let len = reader.read(&mut buf).await?;
+        if len == 0 {
+            return writer.shutdown().await
+        }
+        transform(&mut buf[..len]);
+        writer.write_all(&buf[..len]).await?;
+    }
+}
+
+/// Relays a plain connection with the client to an encrypted connection with the server.
+async fn relay_encrypted(
+    client: TcpStream,
+    upstream: TcpStream,
+    (mut encryptor, mut decryptor): (Aes128CfbEnc, Aes128CfbDec)
+) -> io::Result<()> {
+    let (client_read, client_write) = client.into_split();
+    let (upstream_read, upstream_write) = upstream.into_split();
+    tokio::try_join!(
+        pipe(client_read, upstream_write, |data| azalea_crypto::encrypt_packet(&mut encryptor, data)),
+        pipe(upstream_read, client_write, |data| azalea_crypto::decrypt_packet(&mut decryptor, data))
+    )?;
+    Ok(())
+}
+
+/// How the relay reaches the server.
+pub enum Upstream {
+    /// Through a proxy, which resolves the address of the server itself
+    Proxy(Proxy),
+    /// Directly, at the resolved address of the server
+    Direct(SocketAddr),
+}
+
+/// Where and how the relay forwards connections.
+struct Route {
+    upstream: Upstream,
+    join: Option<SessionJoin>,
+    /// Joins the session server, shared by all the connections of the relay
+    http: reqwest::Client,
+}
+
+/// Forwards a connection accepted by the relay to the server.
+async fn tunnel(mut client: TcpStream, route: &Route, target: &Server) -> io::Result<()> {
+    let handshake = rewrite_handshake(&mut client, target).await?;
+    let mut upstream = match &route.upstream {
+        Upstream::Proxy(proxy) => proxy::connect(proxy, &target.ip, target.port).await?,
+        Upstream::Direct(destination) => TcpStream::connect(destination).await?
+    };
+    upstream.write_all(&handshake.packet).await?;
+    let ciphers = match &route.join {
+        Some(join) if LOGIN_INTENTS.contains(&handshake.intent) => {
+            login(&mut client, &mut upstream, handshake.protocol, join, &route.http).await?
+        }
+        _ => None
+    };
+    match ciphers {
+        Some(ciphers) => relay_encrypted(client, upstream, ciphers).await,
+        None => tokio::io::copy_bidirectional(&mut client, &mut upstream).await.map(|_| ())
+    }
+}
+
+/// A local listener forwarding the game connection of an instance to the server, through a
+/// proxy and/or joining the session server of a Yggdrasil account. The client connects to the
+/// relay instead of the server, whatever version backend it uses.
+pub struct Relay {
+    addr: SocketAddr,
+    error: Arc<Mutex<Option<ConnectionError>>>,
+    task: JoinHandle<()>,
+}
+
+impl Relay {
+    /// Starts relaying connections to `target` through `upstream`.
+    pub async fn start(upstream: Upstream, join: Option<SessionJoin>, target: Server) -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let error = Arc::new(Mutex::new(None));
+        match &upstream {
+            Upstream::Proxy(proxy) => info!("Relaying connections to {target} through {proxy} from {addr}"),
+            Upstream::Direct(destination) => info!("Relaying connections to {target} ({destination}) from {addr}"),
+        }
+        let last_error = error.clone();
+        let task = tokio::spawn(async move {
+            let route = Arc::new(Route { upstream, join, http: reqwest::Client::new() });
+            let target = Arc::new(target);
+            while let Ok((client, _)) = listener.accept().await {
+                let (route, target, last_error) = (route.clone(), target.clone(), last_error.clone());
+                tokio::spawn(async move {
+                    if let Err(e) = tunnel(client, &route, &target).await {
+                        warn!("Relay to {target}: {e}");
+                        let error = match (e.kind(), &route.upstream) {
+                            (io::ErrorKind::PermissionDenied, _) => ConnectionError::Auth(e.to_string()),
+                            (_, Upstream::Proxy(_)) => ConnectionError::Network(format!("Proxy error: {e}")),
+                            _ => ConnectionError::Network(e.to_string())
+                        };
+                        *last_error.lock().unwrap() = Some(error);
+                    }
+                });
+            }
+        });
+        Ok(Self { addr, error, task })
+    }
+
+    /// The address to connect to instead of the server
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The last error of the relay, e.g. because the proxy rejected the connection.
+    pub fn error(&self) -> Option<ConnectionError> {
+        self.error.lock().unwrap().clone()
+    }
+}
+
+impl Drop for Relay {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream
+    };
+    use uuid::Uuid;
+    use crate::{
+        api::Server,
+        client::{
+            network::ConnectionError,
+            ping::{read_packet, read_string, read_varint, write_packet, write_string, write_varint},
+            proxy::{Proxy, ProxyType},
+            relay::{write_bytes, Relay, Upstream},
+            yggdrasil::{SessionJoin, YggdrasilServer}
+        },
+        test_support::StandIn
+    };
+
+    /// A 1024-bit RSA public key, as sent by servers in their encryption request.
+    const PUBLIC_KEY: &str = "MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQC+8rxeWYzh608UmUcv1w6qxYLRQhryhNYhULjBc9I7xdci9SBwttXP1MC7oFgDGQBmoFlMY1/6lq++i6BDoPk9s5k0+SOsX2O8jdhzlE4zlzlsAf+v1D4pyf5FzwPULPQrdlnD5+rsG6VEMGUWA8oXUcT5NmLBG4SYZQECbTRI2wIDAQAB";
+
+    async fn send_handshake(client: &mut TcpStream, protocol: i32, port: u16) {
+        let mut handshake = Vec::new();
+        write_varint(&mut handshake, protocol);
+        write_string(&mut handshake, "127.0.0.1");
+        handshake.extend_from_slice(&port.to_be_bytes());
+        write_varint(&mut handshake, 2);
+        write_packet(client, 0x00, &handshake).await.unwrap();
+    }
+
+    /// Stands in for both a SOCKS5 proxy requiring credentials, and the server behind it.
+    #[tokio::test]
+    async fn socks5_relay() {
+        let stand_in = StandIn::once(|mut stream| async move {
+            let mut greeting = [0; 4];
+            stream.read_exact(&mut greeting).await.unwrap();
+            assert_eq!(greeting, [0x05, 0x02, 0x00, 0x02]);
+            stream.write_all(&[0x05, 0x02]).await.unwrap();
+            let mut auth = [0; 12];
+            stream.read_exact(&mut auth).await.unwrap();
+            assert_eq!(&auth, b"\x01\x03bot\x06secret");
+            stream.write_all(&[0x01, 0x00]).await.unwrap();
+            // the server is resolved by the proxy
+            let mut request = [0; 23];
+            stream.read_exact(&mut request).await.unwrap();
+            assert_eq!(&request, b"\x05\x01\x00\x03\x10play.example.net\x63\xDD");
+            stream.write_all(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0]).await.unwrap();
+
+            // now talking to the "server"
+            let (id, data) = read_packet(&mut stream).await.unwrap();
+            let mut slice = data.as_slice();
+            assert_eq!(id, 0x00);
+            assert_eq!(read_varint(&mut slice).await.unwrap(), 772);
+            assert_eq!(read_string(&mut slice).await.unwrap(), "play.example.net");
+            assert_eq!(slice, [0x63, 0xDD, 0x02]);
+            stream.write_all(b"pong").await.unwrap();
+        }).await;
+        let proxy = Proxy {
+            kind: ProxyType::Socks5,
+            host: "127.0.0.1".to_string(),
+            port: stand_in.addr().port(),
+            username: Some("bot".to_string()),
+            password: Some("secret".to_string())
+        };
+
+        let target = Server::new("example".to_string(), "play.example.net".to_string(), 25565);
+        let relay = Relay::start(Upstream::Proxy(proxy), None, target).await.unwrap();
+        let mut client = TcpStream::connect(relay.addr()).await.unwrap();
+        send_handshake(&mut client, 772, relay.addr().port()).await;
+        let mut response = [0; 4];
+        client.read_exact(&mut response).await.unwrap();
+        assert_eq!(&response, b"pong");
+        stand_in.finish().await;
+        assert!(relay.error().is_none());
+    }
+
+    /// Stands in for an online-mode server, and for a session server rejecting the join.
+    #[tokio::test]
+    async fn session_join_relay() {
+        use base64::{Engine, engine::general_purpose::STANDARD};
+        let session_server = StandIn::http(|request| {
+            assert_eq!((request.method.as_str(), request.path.as_str()), ("POST", "/session/minecraft/join"));
+            ("403 Forbidden", r#"{"errorMessage":"Invalid token, try again"}"#.to_string())
+        }).await;
+        let server = StandIn::once(|mut stream| async move {
+            read_packet(&mut stream).await.unwrap();
+            let (id, login_start) = read_packet(&mut stream).await.unwrap();
+            assert_eq!((id, read_string(&mut login_start.as_slice()).await.unwrap().as_str()), (0x00, "Steve"));
+            let mut hello = Vec::new();
+            write_string(&mut hello, "");
+            write_bytes(&mut hello, &STANDARD.decode(PUBLIC_KEY).unwrap());
+            write_bytes(&mut hello, &[1, 2, 3, 4]);
+            hello.push(1);
+            write_packet(&mut stream, 0x01, &hello).await.unwrap();
+            // the relay gives up before answering
+            assert!(read_packet(&mut stream).await.is_err());
+        }).await;
+        let destination = server.addr();
+
+        let join = SessionJoin {
+            server: YggdrasilServer {
+                auth_url: String::new(),
+                session_url: session_server.url(),
+                services_url: None
+            },
+            access_token: "access".to_string(),
+            uuid: Uuid::new_v4()
+        };
+        let target = Server::new("example".to_string(), "127.0.0.1".to_string(), destination.port());
+        let relay = Relay::start(Upstream::Direct(destination), Some(join), target).await.unwrap();
+        let mut client = TcpStream::connect(relay.addr()).await.unwrap();
+        send_handshake(&mut client, 772, relay.addr().port()).await;
+        let mut login_start = Vec::new();
+        write_string(&mut login_start, "Steve");
+        login_start.extend_from_slice(Uuid::new_v4().as_bytes());
+        write_packet(&mut client, 0x00, &login_start).await.unwrap();
+        // the encryption request never reaches the client
+        assert!(read_packet(&mut client).await.is_err());
+        server.finish().await;
+        // the error is recorded once the relay has closed both connections
+        let mut error = None;
+        for _ in 0..50 {
+            error = relay.error();
+            if error.is_some() {
+                break
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(matches!(error, Some(ConnectionError::Auth(e)) if e.contains("Invalid token")));
+    }
+}
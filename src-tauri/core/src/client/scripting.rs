@@ -0,0 +1,252 @@
+use std::{
+    fs,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime}
+};
+use azalea::{
+    entity::{metadata::Health, Position},
+    prelude::*,
+    protocol::packets::ProtocolPacket,
+    SprintDirection, Vec3, WalkDirection
+};
+use azalea_chat::style::{Ansi, ChatFormatting};
+use log::{info, warn};
+use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+use crate::client::hooks::chatlog::ChatBuffer;
+
+/// The directory, relative to the data directory, holding the scripts instances may run.
+pub const SCRIPTS_DIR: &str = "scripts";
+
+/// How often the script file is checked for changes.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The maximum number of operations a single callback may run, so that an infinite loop
+/// can't freeze the client.
+const MAX_OPERATIONS: u64 = 500_000;
+
+/// A handle to the client, passed as the first argument of every callback.
+#[derive(Clone)]
+struct Bot(Client);
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+fn walk_direction(direction: &str) -> ScriptResult<WalkDirection> {
+    Ok(match direction {
+        "none" => WalkDirection::None,
+        "forward" => WalkDirection::Forward,
+        "backward" => WalkDirection::Backward,
+        "left" => WalkDirection::Left,
+        "right" => WalkDirection::Right,
+        "forward_left" => WalkDirection::ForwardLeft,
+        "forward_right" => WalkDirection::ForwardRight,
+        "backward_left" => WalkDirection::BackwardLeft,
+        "backward_right" => WalkDirection::BackwardRight,
+        _ => return Err(format!("Unknown direction: {direction}").into())
+    })
+}
+
+fn sprint_direction(direction: &str) -> ScriptResult<SprintDirection> {
+    Ok(match direction {
+        "forward" => SprintDirection::Forward,
+        "forward_left" => SprintDirection::ForwardLeft,
+        "forward_right" => SprintDirection::ForwardRight,
+        _ => return Err(format!("Unknown sprint direction: {direction}").into())
+    })
+}
+
+/// Creates the sandboxed engine scripts run in. Scripts have no access to the filesystem
+/// or the network; `print` and `debug` write to the instance's chat.
+fn create_engine(chat: ChatBuffer) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(64)
+        .set_max_expr_depths(64, 64)
+        .set_max_string_size(64 * 1024)
+        .set_max_array_size(10_000)
+        .set_max_map_size(10_000);
+    engine.disable_symbol("eval");
+    // the default resolver would let `import` read any file
+    engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+
+    let printer = chat.clone();
+    engine.on_print(move |text| printer.push(format!("[script] {text}")));
+    engine.on_debug(move |text, _, pos| chat.push(format!("[script] {pos:?}: {text}")));
+
+    engine.register_type_with_name::<Bot>("Bot")
+        .register_get("username", |bot: &mut Bot| bot.0.username())
+        .register_fn("chat", |bot: &mut Bot, message: &str| bot.0.chat(message))
+        .register_fn("position", |bot: &mut Bot| match bot.0.get_component::<Position>() {
+            Some(position) => {
+                let mut map = Map::new();
+                map.insert("x".into(), position.x.into());
+                map.insert("y".into(), position.y.into());
+                map.insert("z".into(), position.z.into());
+                Dynamic::from_map(map)
+            }
+            None => Dynamic::UNIT
+        })
+        .register_fn("health", |bot: &mut Bot| match bot.0.get_component::<Health>() {
+            Some(health) => Dynamic::from_float(*health as f64),
+            None => Dynamic::UNIT
+        })
+        .register_fn("look_at", |bot: &mut Bot, x: f64, y: f64, z: f64| bot.0.look_at(Vec3::new(x, y, z)))
+        .register_fn("look", |bot: &mut Bot, yaw: f64, pitch: f64| bot.0.set_direction(yaw as f32, pitch as f32))
+        .register_fn("walk", |bot: &mut Bot, direction: &str| -> ScriptResult<()> {
+            bot.0.walk(walk_direction(direction)?);
+            Ok(())
+        })
+        .register_fn("sprint", |bot: &mut Bot, direction: &str| -> ScriptResult<()> {
+            bot.0.sprint(sprint_direction(direction)?);
+            Ok(())
+        })
+        .register_fn("stop", |bot: &mut Bot| bot.0.walk(WalkDirection::None))
+        .register_fn("jump", |bot: &mut Bot| bot.0.jump());
+    engine
+}
+
+#[derive(Default)]
+struct Script {
+    ast: Option<AST>,
+    /// The state kept between callbacks, bound to `this`
+    this: Dynamic,
+    /// Whether the file was read at least once, successfully or not
+    loaded: bool,
+    /// Whether `on_tick` failed since the script was loaded. It is then no longer called, as
+    /// it would report the same error 20 times per second.
+    tick_failed: bool,
+    modified: Option<SystemTime>,
+    checked: Option<Instant>,
+}
+
+/// Runs a Rhai script attached to an instance for the duration of a session.
+///
+/// The script gets a callback for each event handled by the client, if it defines it:
+/// `on_init(bot)`, `on_tick(bot)`, `on_chat(bot, message, sender)`,
+/// `on_disconnect(bot, reason)` and `on_packet(bot, name)`. Trailing parameters may be
+/// omitted. `this` is an object map kept between callbacks, reset when the script is reloaded.
+///
+/// The script is reloaded whenever its file changes. Compilation and runtime errors are
+/// sent to the instance's chat.
+pub struct ScriptHost {
+    path: PathBuf,
+    engine: Engine,
+    chat: ChatBuffer,
+    script: Mutex<Script>,
+}
+
+impl ScriptHost {
+    pub fn new(path: PathBuf, chat: ChatBuffer) -> Self {
+        Self {
+            engine: create_engine(chat.clone()),
+            path,
+            chat,
+            script: Mutex::new(Script::default())
+        }
+    }
+
+    fn report(&self, message: String) {
+        warn!("Script {:?}: {message}", self.path);
+        self.chat.push(format!("{}[script] {message}", Ansi::rgb(ChatFormatting::Red.color().unwrap())));
+    }
+
+    /// Recompiles the script if its file has changed since it was last loaded.
+    fn reload(&self, script: &mut Script) {
+        if script.checked.is_some_and(|checked| checked.elapsed() < RELOAD_INTERVAL) {
+            return
+        }
+        script.checked = Some(Instant::now());
+        let modified = fs::metadata(&self.path).and_then(|metadata| metadata.modified()).ok();
+        if script.loaded && modified == script.modified {
+            return
+        }
+        script.loaded = true;
+        script.modified = modified;
+        script.tick_failed = false;
+        script.this = Dynamic::from_map(Map::new());
+        script.ast = match fs::read_to_string(&self.path) {
+            Ok(source) => match self.engine.compile(source) {
+                Ok(ast) => {
+                    info!("Loaded script {:?}", self.path);
+                    Some(ast)
+                }
+                Err(e) => {
+                    self.report(format!("Failed to compile: {e}"));
+                    None
+                }
+            },
+            Err(e) => {
+                self.report(format!("Failed to read {:?}: {e}", self.path));
+                None
+            }
+        };
+    }
+
+    /// Calls the callback of the event, if the script defines it.
+    pub fn dispatch(&self, client: &Client, event: &Event) {
+        let name = match event {
+            Event::Init => "on_init",
+            Event::Tick => "on_tick",
+            Event::Chat(_) => "on_chat",
+            Event::Disconnect(_) => "on_disconnect",
+            Event::Packet(_) => "on_packet",
+            _ => return
+        };
+        let mut guard = self.script.lock().unwrap();
+        self.reload(&mut guard);
+        let Script { ast: Some(ast), this, tick_failed, .. } = &mut *guard else {
+            return
+        };
+        if name == "on_tick" && *tick_failed {
+            return
+        }
+        let Some(arity) = ast.iter_functions()
+            .find(|function| function.name == name)
+            .map(|function| function.params.len()) else {
+            return
+        };
+
+        let mut args = vec![Dynamic::from(Bot(client.clone()))];
+        match event {
+            Event::Chat(message) => {
+                args.push(message.message().to_string().into());
+                args.push(message.sender().unwrap_or_default().into());
+            }
+            Event::Disconnect(reason) => {
+                args.push(reason.as_ref().map(|reason| reason.to_string()).unwrap_or_default().into());
+            }
+            Event::Packet(packet) => args.push(packet.name().into()),
+            _ => {}
+        }
+        args.truncate(arity);
+
+        let options = CallFnOptions::new().eval_ast(false).bind_this_ptr(this);
+        if let Err(e) = self.engine.call_fn_with_options::<Dynamic>(options, &mut Scope::new(), ast, name, args) {
+            let message = if name == "on_tick" {
+                *tick_failed = true;
+                format!("Error in {name}, disabled until the script changes: {e}")
+            } else {
+                format!("Error in {name}: {e}")
+            };
+            drop(guard);
+            self.report(message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{hooks::chatlog::ChatBuffer, scripting::create_engine};
+
+    #[test]
+    fn script_sandbox() {
+        let chat = ChatBuffer::default();
+        let engine = create_engine(chat.clone());
+        engine.run(r#"print("hello " + 40 + 2)"#).unwrap();
+        assert_eq!(chat.since(0)[0].message, "[script] hello 402");
+
+        assert!(engine.run("loop {}").is_err());
+        assert!(engine.run(r#"eval("1 + 1")"#).is_err());
+        assert!(engine.run(r#"import "scripts/other" as other;"#).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex}
+};
+use azalea::PlayerInfo;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How long a client must have been playing before players added to its tab list are
+/// reported as joining, in ticks. The players already online are all added when it joins.
+const SETTLE_TICKS: u32 = 60;
+
+/// A player of the tab list.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TabEntry {
+    pub uuid: Uuid,
+    pub name: String,
+    /// The latency reported by the server, in milliseconds
+    pub ping: i32,
+    /// e.g. `survival`
+    pub game_mode: String,
+    /// The name shown in the tab list, if it differs from the username
+    pub display_name: Option<String>,
+}
+
+impl From<&PlayerInfo> for TabEntry {
+    fn from(info: &PlayerInfo) -> Self {
+        Self {
+            uuid: info.uuid,
+            name: info.profile.name.clone(),
+            ping: info.latency,
+            game_mode: format!("{:?}", info.gamemode).to_lowercase(),
+            display_name: info.display_name.as_ref().map(|name| name.to_string())
+        }
+    }
+}
+
+/// The tab list of an instance, players sorted by name.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct TabList {
+    pub players: Vec<TabEntry>,
+    pub header: String,
+    pub footer: String,
+}
+
+impl TabList {
+    /// The lines printed by the `.list` command.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("There are {} player(s) online:", self.players.len())];
+        lines.extend(self.players.iter().map(|player| match &player.display_name {
+            Some(display) if *display != player.name => {
+                format!(" - {} ({display}) [{}ms, {}]", player.name, player.ping, player.game_mode)
+            }
+            _ => format!(" - {} [{}ms, {}]", player.name, player.ping, player.game_mode)
+        }));
+        lines
+    }
+}
+
+#[derive(Default)]
+struct TabListState {
+    players: HashMap<Uuid, TabEntry>,
+    header: String,
+    footer: String,
+    ticks: u32,
+}
+
+/// A shared handle to the tab list of an instance, updated by its client thread.
+#[derive(Clone, Default)]
+pub struct TabListHandle(Arc<Mutex<TabListState>>);
+
+impl TabListHandle {
+    pub fn get(&self) -> TabList {
+        let state = self.0.lock().unwrap();
+        let mut players: Vec<_> = state.players.values().cloned().collect();
+        players.sort_by_key(|player| player.name.to_lowercase());
+        TabList {
+            players,
+            header: state.header.clone(),
+            footer: state.footer.clone()
+        }
+    }
+
+    /// Adds or updates a player. Returns whether they should be reported as joining.
+    pub fn add(&self, entry: TabEntry) -> bool {
+        let mut state = self.0.lock().unwrap();
+        let settled = state.ticks >= SETTLE_TICKS;
+        state.players.insert(entry.uuid, entry).is_none() && settled
+    }
+
+    /// Updates a player, adding them if they aren't listed yet.
+    pub fn update(&self, entry: TabEntry) {
+        self.0.lock().unwrap().players.insert(entry.uuid, entry);
+    }
+
+    /// Removes a player, returning them if they were listed.
+    pub fn remove(&self, uuid: &Uuid) -> Option<TabEntry> {
+        self.0.lock().unwrap().players.remove(uuid)
+    }
+
+    pub fn set_header_footer(&self, header: String, footer: String) {
+        let mut state = self.0.lock().unwrap();
+        state.header = header;
+        state.footer = footer;
+    }
+
+    /// Called on every tick while the client is playing.
+    pub fn tick(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.ticks = state.ticks.saturating_add(1);
+    }
+
+    /// Empties the tab list, once the client has left the server.
+    pub fn clear(&self) {
+        *self.0.lock().unwrap() = TabListState::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+    use crate::client::tablist::{TabEntry, TabListHandle, SETTLE_TICKS};
+
+    #[test]
+    fn tab_list_tracking() {
+        let entry = |name: &str| TabEntry {
+            uuid: Uuid::new_v4(),
+            name: name.to_string(),
+            ping: 42,
+            game_mode: "survival".to_string(),
+            display_name: None
+        };
+        let tab_list = TabListHandle::default();
+        let steve = entry("Steve");
+        // players already online when joining aren't reported
+        assert!(!tab_list.add(steve.clone()));
+        for _ in 0..SETTLE_TICKS {
+            tab_list.tick();
+        }
+        assert!(tab_list.add(entry("alex")));
+        assert!(!tab_list.add(steve.clone()));
+
+        let list = tab_list.get();
+        assert_eq!(list.players.iter().map(|player| player.name.as_str()).collect::<Vec<_>>(), ["alex", "Steve"]);
+        assert_eq!(list.lines()[2], " - Steve [42ms, survival]");
+
+        assert_eq!(tab_list.remove(&steve.uuid), Some(steve));
+        assert_eq!(tab_list.get().players.len(), 1);
+    }
+}
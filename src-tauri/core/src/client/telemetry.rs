@@ -0,0 +1,108 @@
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc, Mutex
+};
+use azalea::{
+    entity::{metadata::Health, LookDirection, Position},
+    local_player::{Hunger, LocalGameMode},
+    prelude::*,
+    world::InstanceName
+};
+use serde::{Deserialize, Serialize};
+
+/// How often the telemetry of a playing instance is sampled and published, in ticks.
+pub const TELEMETRY_INTERVAL_TICKS: u32 = 20;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Experience {
+    pub level: u32,
+    /// Progress towards the next level, from 0 to 1
+    pub progress: f32,
+    pub total: u32,
+}
+
+/// A snapshot of the in-game state of a client.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Telemetry {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub health: f32,
+    pub food: u32,
+    pub saturation: f32,
+    pub experience: Experience,
+    /// e.g. `survival`
+    pub game_mode: String,
+    /// e.g. `minecraft:overworld`
+    pub dimension: String,
+}
+
+/// A shared handle to the latest [`Telemetry`] of an instance, updated by its client thread.
+#[derive(Clone, Default)]
+pub struct TelemetryHandle {
+    latest: Arc<Mutex<Option<Telemetry>>>,
+    /// Only sent by the server when it changes, so it is kept from the last packet
+    experience: Arc<Mutex<Experience>>,
+    ticks: Arc<AtomicU32>,
+}
+
+impl TelemetryHandle {
+    /// The last sampled telemetry, `None` if the instance isn't in game.
+    pub fn get(&self) -> Option<Telemetry> {
+        self.latest.lock().unwrap().clone()
+    }
+
+    /// Forgets the last telemetry, once the client has left the game.
+    pub fn clear(&self) {
+        *self.latest.lock().unwrap() = None;
+        *self.experience.lock().unwrap() = Experience::default();
+        self.ticks.store(0, Ordering::Relaxed);
+    }
+
+    pub fn set_experience(&self, experience: Experience) {
+        *self.experience.lock().unwrap() = experience;
+    }
+
+    /// Called on every tick; samples the client's components every
+    /// [`TELEMETRY_INTERVAL_TICKS`], and returns the telemetry if it has changed since.
+    pub fn tick(&self, client: &Client) -> Option<Telemetry> {
+        if self.ticks.fetch_add(1, Ordering::Relaxed) % TELEMETRY_INTERVAL_TICKS != 0 {
+            return None
+        }
+        let telemetry = self.sample(client)?;
+        let mut latest = self.latest.lock().unwrap();
+        if latest.as_ref() == Some(&telemetry) {
+            return None
+        }
+        *latest = Some(telemetry.clone());
+        Some(telemetry)
+    }
+
+    /// Reads the telemetry from the client's components, `None` if it hasn't spawned yet.
+    fn sample(&self, client: &Client) -> Option<Telemetry> {
+        let position = client.get_component::<Position>()?;
+        let direction = client.get_component::<LookDirection>()?;
+        let (food, saturation) = client.get_component::<Hunger>()
+            .map(|hunger| (hunger.food, hunger.saturation))
+            .unwrap_or_default();
+        Some(Telemetry {
+            x: position.x,
+            y: position.y,
+            z: position.z,
+            yaw: direction.y_rot,
+            pitch: direction.x_rot,
+            health: client.get_component::<Health>().map(|health| *health).unwrap_or_default(),
+            food,
+            saturation,
+            experience: self.experience.lock().unwrap().clone(),
+            game_mode: client.get_component::<LocalGameMode>()
+                .map(|mode| format!("{:?}", mode.current).to_lowercase())
+                .unwrap_or_default(),
+            dimension: client.get_component::<InstanceName>()
+                .map(|name| name.to_string())
+                .unwrap_or_default(),
+        })
+    }
+}
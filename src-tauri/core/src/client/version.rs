@@ -0,0 +1,232 @@
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+    fs,
+    hash::{Hash, Hasher},
+    path::Path,
+    str::FromStr,
+    sync::{Arc, LazyLock, RwLock}
+};
+use log::{error, info};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Accepted in place of a version when creating a connection, to use the version of the server.
+pub const AUTO_VERSION: &str = "auto";
+
+/// The file, relative to the data directory, adding versions to the embedded version table or
+/// replacing some of them, in the same format as `versions.json` in this module.
+pub const VERSIONS_FILE: &str = "versions.json";
+
+const EMBEDDED_VERSIONS: &str = include_str!("versions.json");
+
+/// How a version is served to the server.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// Natively supported by azalea
+    Native,
+    /// Translated from azalea's native version by a ViaVersion proxy
+    ViaVersion,
+}
+
+/// An entry of the version table.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VersionInfo {
+    /// e.g. `1.21.4`
+    pub name: String,
+    /// The protocol number, as sent in the handshake and in status responses
+    pub protocol: i32,
+    /// The release date, e.g. `2024-12-03`
+    pub released: String,
+    pub backend: Backend,
+}
+
+/// The supported versions, sorted by protocol number and release date.
+static REGISTRY: LazyLock<RwLock<Vec<Arc<VersionInfo>>>> = LazyLock::new(|| {
+    let versions: Vec<VersionInfo> = serde_json::from_str(EMBEDDED_VERSIONS)
+        .expect("Embedded version table is invalid");
+    let mut registry = Vec::new();
+    merge(&mut registry, versions);
+    RwLock::new(registry)
+});
+
+/// Adds `versions` to `registry`, replacing the entries with the same name.
+fn merge(registry: &mut Vec<Arc<VersionInfo>>, versions: Vec<VersionInfo>) {
+    for version in versions {
+        registry.retain(|existing| existing.name != version.name);
+        registry.push(Arc::new(version));
+    }
+    registry.sort_by(|a, b| (a.protocol, &a.released).cmp(&(b.protocol, &b.released)));
+}
+
+/// Loads the [`VERSIONS_FILE`] of the data directory into the version table, if it exists.
+///
+/// Must be called before loading any data file holding versions, which would otherwise fail
+/// to parse the versions added by the file.
+pub fn load_overrides(dir: &Path) {
+    let path = dir.join(VERSIONS_FILE);
+    if !path.exists() {
+        return
+    }
+    let versions = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str::<Vec<VersionInfo>>(&content).map_err(|e| e.to_string()));
+    match versions {
+        Ok(versions) => {
+            info!("Loaded {} version(s) from {path:?}", versions.len());
+            merge(&mut REGISTRY.write().unwrap(), versions);
+        }
+        Err(e) => error!("Failed to load version table from {path:?}: {e}")
+    }
+}
+
+/// A Minecraft version of the version table, e.g. `1.21.4`. Serialized as its name.
+#[derive(Debug, Clone)]
+pub struct Version(Arc<VersionInfo>);
+
+impl Version {
+    pub fn from_string(version: &str) -> Option<Self> {
+        REGISTRY.read().unwrap().iter()
+            .find(|info| info.name == version)
+            .map(|info| Self(info.clone()))
+    }
+
+    /// The latest version with the given protocol number, `None` if it isn't supported.
+    pub fn from_protocol(protocol: i32) -> Option<Self> {
+        REGISTRY.read().unwrap().iter()
+            .rfind(|info| info.protocol == protocol)
+            .map(|info| Self(info.clone()))
+    }
+
+    /// All supported versions, oldest first.
+    pub fn all() -> Vec<Version> {
+        REGISTRY.read().unwrap().iter().map(|info| Self(info.clone())).collect()
+    }
+
+    pub fn info(&self) -> &VersionInfo {
+        &self.0
+    }
+
+    pub fn protocol(&self) -> i32 {
+        self.0.protocol
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.0.backend
+    }
+}
+
+impl Default for Version {
+    /// The latest supported version
+    fn default() -> Self {
+        Self(REGISTRY.read().unwrap().last().expect("Version table is empty").clone())
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.name == other.0.name
+    }
+}
+
+impl Eq for Version {}
+
+impl Hash for Version {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.name.hash(state);
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.0.protocol, &self.0.released, &self.0.name)
+            .cmp(&(other.0.protocol, &other.0.released, &other.0.name))
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.0.name)
+    }
+}
+
+impl<'a> Deserialize<'a> for Version {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'a>
+    {
+        let s = String::deserialize(deserializer)?;
+        Version::from_string(&s).ok_or_else(|| serde::de::Error::custom("Invalid version"))
+    }
+}
+
+impl FromStr for Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_string(s)
+            .ok_or_else(|| format!("Invalid version: {s}"))
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::client::version::{merge, Backend, Version, VersionInfo, EMBEDDED_VERSIONS};
+
+    #[test]
+    fn version_string_conversion() {
+        let ver = Version::from_str("1.20.5").unwrap();
+        assert_eq!(ver.protocol(), 766);
+        assert_eq!(serde_json::to_string(&ver).unwrap(), r#""1.20.5""#);
+
+        let str = Version::from_str("1.16.5").unwrap().to_string();
+        assert_eq!(str.as_str(), "1.16.5");
+        assert!(Version::from_str("1.8.9").is_err());
+    }
+
+    #[test]
+    fn version_protocol_conversion() {
+        assert_eq!(Version::from_protocol(754).unwrap().to_string(), "1.16.5");
+        assert_eq!(Version::from_protocol(769).unwrap().to_string(), "1.21.4");
+        assert_eq!(Version::from_protocol(762).unwrap().to_string(), "1.19.4");
+        assert_eq!(Version::from_protocol(47), None);
+        assert_eq!(Version::from_protocol(-1), None);
+        assert!(Version::all().iter().all(|version| Version::from_protocol(version.protocol()).is_some()));
+    }
+
+    #[test]
+    fn version_table() {
+        let all = Version::all();
+        assert_eq!(all.first().unwrap().to_string(), "1.16");
+        assert!(all.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(Version::default(), *all.last().unwrap());
+        assert_eq!(all.iter().filter(|version| version.backend() == Backend::Native).count(), 1);
+
+        let mut registry = Vec::new();
+        merge(&mut registry, serde_json::from_str(EMBEDDED_VERSIONS).unwrap());
+        let count = registry.len();
+        merge(&mut registry, vec![
+            VersionInfo { name: "1.21.4".to_string(), protocol: 769, released: "2024-12-03".to_string(), backend: Backend::Native },
+            VersionInfo { name: "1.99".to_string(), protocol: 999, released: "2099-01-01".to_string(), backend: Backend::ViaVersion },
+        ]);
+        assert_eq!(registry.len(), count + 1);
+        assert_eq!(registry.iter().find(|info| info.name == "1.21.4").unwrap().backend, Backend::Native);
+        assert_eq!(registry.last().unwrap().name, "1.99");
+    }
+}
@@ -0,0 +1,246 @@
+use std::time::Duration;
+use azalea_auth::ProfileResponse;
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+use crate::client::auth::MinecraftProfile;
+
+/// Yggdrasil tokens don't tell when they expire. They are considered to expire after this
+/// long, so that they are refreshed regularly like Microsoft tokens.
+pub const TOKEN_LIFETIME: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The endpoints of a Yggdrasil-compatible authentication server, such as the ones used with
+/// authlib-injector, e.g. `https://auth.example.net/authserver` and
+/// `https://auth.example.net/sessionserver`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct YggdrasilServer {
+    /// Serves `/authenticate` and `/refresh`
+    pub auth_url: String,
+    /// Serves `/session/minecraft/join`, called when joining online-mode servers
+    pub session_url: String,
+    /// Serves `/minecraft/profile`, for the skins and capes of the account; optional
+    #[serde(default)]
+    pub services_url: Option<String>,
+}
+
+impl YggdrasilServer {
+    fn endpoint(base: &str, path: &str) -> String {
+        format!("{}/{path}", base.trim_end_matches('/'))
+    }
+}
+
+/// The tokens of a Yggdrasil session. The client token is chosen on login, and must be
+/// sent along with the access token to refresh it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct YggdrasilToken {
+    pub access_token: String,
+    pub client_token: String,
+}
+
+#[derive(Deserialize)]
+struct SelectedProfile {
+    id: Uuid,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenResponse {
+    access_token: String,
+    client_token: String,
+    selected_profile: Option<SelectedProfile>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ErrorResponse {
+    error: Option<String>,
+    error_message: Option<String>,
+}
+
+/// Posts a JSON request, turning error responses into their message.
+async fn post(client: &reqwest::Client, url: &str, body: serde_json::Value) -> Result<reqwest::Response, String> {
+    let response = client
+        .post(url)
+        .json(&body)
+        .send().await
+        .map_err(|e| format!("Failed to reach {url}: {e}"))?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response)
+    }
+    let message = match response.json::<ErrorResponse>().await {
+        Ok(ErrorResponse { error_message: Some(message), .. }) => message,
+        Ok(ErrorResponse { error: Some(error), .. }) => error,
+        _ => status.to_string()
+    };
+    Err(message)
+}
+
+async fn read_token(response: reqwest::Response) -> Result<(YggdrasilToken, Option<SelectedProfile>), String> {
+    let response: TokenResponse = response.json().await
+        .map_err(|e| format!("Invalid response from the authentication server: {e}"))?;
+    let token = YggdrasilToken {
+        access_token: response.access_token,
+        client_token: response.client_token
+    };
+    Ok((token, response.selected_profile))
+}
+
+/// Fetches the skins and capes of the account from the services, if the server has any.
+/// They are only shown to the user, so failures leave the profile as is.
+async fn complete_profile(
+    client: &reqwest::Client,
+    server: &YggdrasilServer,
+    access_token: &str,
+    profile: MinecraftProfile
+) -> MinecraftProfile {
+    let Some(services) = &server.services_url else {
+        return profile
+    };
+    let response = client
+        .get(YggdrasilServer::endpoint(services, "minecraft/profile"))
+        .bearer_auth(access_token)
+        .send().await;
+    let response = match response {
+        Ok(response) if response.status().is_success() => response.json::<ProfileResponse>().await,
+        _ => return profile
+    };
+    match response {
+        Ok(response) => MinecraftProfile::from(&response),
+        Err(_) => profile
+    }
+}
+
+/// Logs into a Yggdrasil account with its username, or email, and password.
+///
+/// # Returns
+/// The tokens of the new session along with the profile of the account, or the error message
+/// of the authentication server
+pub async fn authenticate(
+    client: &reqwest::Client,
+    server: &YggdrasilServer,
+    username: &str,
+    password: &str
+) -> Result<(YggdrasilToken, MinecraftProfile), String> {
+    let response = post(client, &YggdrasilServer::endpoint(&server.auth_url, "authenticate"), json!({
+        "agent": { "name": "Minecraft", "version": 1 },
+        "username": username,
+        "password": password,
+        "clientToken": Uuid::new_v4().simple().to_string(),
+        "requestUser": false
+    })).await?;
+    let (token, profile) = read_token(response).await?;
+    let profile = profile.ok_or_else(|| format!("{username} has no Minecraft profile"))?;
+    info!("Authenticated {} with {}", profile.name, server.auth_url);
+    let profile = MinecraftProfile {
+        uuid: profile.id,
+        username: profile.name,
+        skins: None,
+        capes: None,
+        authenticated: true
+    };
+    Ok((token.clone(), complete_profile(client, server, &token.access_token, profile).await))
+}
+
+/// Exchanges the tokens of a session for new ones, invalidating the previous access token.
+pub async fn refresh(
+    client: &reqwest::Client,
+    server: &YggdrasilServer,
+    token: &YggdrasilToken
+) -> Result<YggdrasilToken, String> {
+    let response = post(client, &YggdrasilServer::endpoint(&server.auth_url, "refresh"), json!({
+        "accessToken": token.access_token,
+        "clientToken": token.client_token,
+        "requestUser": false
+    })).await?;
+    read_token(response).await.map(|(token, _)| token)
+}
+
+/// Tells the session server that the account is joining a server, which the server checks
+/// before letting it in.
+///
+/// # Parameters
+/// * `server_hash` - the hash of the server ID, the server's public key and the shared secret
+pub async fn join(
+    client: &reqwest::Client,
+    server: &YggdrasilServer,
+    access_token: &str,
+    uuid: &Uuid,
+    server_hash: &str
+) -> Result<(), String> {
+    post(client, &YggdrasilServer::endpoint(&server.session_url, "session/minecraft/join"), json!({
+        "accessToken": access_token,
+        "selectedProfile": uuid.simple().to_string(),
+        "serverId": server_hash
+    })).await?;
+    Ok(())
+}
+
+/// What the relay of an instance needs to join the session server of a Yggdrasil account,
+/// see [`crate::client::relay::Relay`].
+#[derive(Clone, Debug)]
+pub struct SessionJoin {
+    pub server: YggdrasilServer,
+    pub access_token: String,
+    pub uuid: Uuid,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use uuid::Uuid;
+    use crate::{
+        client::yggdrasil::{authenticate, join, refresh, YggdrasilServer, YggdrasilToken},
+        test_support::StandIn
+    };
+
+    /// Stands in for a Yggdrasil server, accepting one account.
+    #[tokio::test]
+    async fn yggdrasil_session() {
+        let stand_in = StandIn::http(|request| {
+            let body = request.json();
+            assert!(request.headers.contains("content-type: application/json"));
+            match request.path.as_str() {
+                "/authserver/authenticate" if body["password"] == "hunter2" => ("200 OK", format!(
+                    r#"{{"accessToken":"access-1","clientToken":{},"selectedProfile":{{"id":"8667ba71b85a4004af54457a9734eed7","name":"Steve"}}}}"#,
+                    body["clientToken"]
+                )),
+                "/authserver/authenticate" => (
+                    "403 Forbidden",
+                    r#"{"error":"ForbiddenOperationException","errorMessage":"Invalid credentials."}"#.to_string()
+                ),
+                "/authserver/refresh" => {
+                    assert_eq!((&body["accessToken"], &body["clientToken"]), (&Value::from("access-1"), &Value::from("client")));
+                    ("200 OK", r#"{"accessToken":"access-2","clientToken":"client"}"#.to_string())
+                }
+                "/sessionserver/session/minecraft/join" => {
+                    assert_eq!(body["selectedProfile"], "8667ba71b85a4004af54457a9734eed7");
+                    assert_eq!(body["serverId"], "-7c9d5b0044c130109a5d7b5fb5c317c02b4e28c1");
+                    ("204 No Content", String::new())
+                }
+                _ => ("404 Not Found", String::new())
+            }
+        }).await;
+
+        let client = reqwest::Client::new();
+        let server = YggdrasilServer {
+            auth_url: format!("{}/authserver/", stand_in.url()),
+            session_url: format!("{}/sessionserver", stand_in.url()),
+            services_url: None
+        };
+        let wrong = authenticate(&client, &server, "steve@example.net", "wrong").await;
+        assert_eq!(wrong.unwrap_err(), "Invalid credentials.");
+        let (token, profile) = authenticate(&client, &server, "steve@example.net", "hunter2").await.unwrap();
+        assert_eq!(token.access_token, "access-1");
+        assert_eq!(profile.username, "Steve");
+        assert_eq!(profile.uuid, Uuid::parse_str("8667ba71-b85a-4004-af54-457a9734eed7").unwrap());
+        assert!(profile.authenticated);
+
+        let token = YggdrasilToken { client_token: "client".to_string(), ..token };
+        let token = refresh(&client, &server, &token).await.unwrap();
+        assert_eq!(token.access_token, "access-2");
+        join(&client, &server, &token.access_token, &profile.uuid, "-7c9d5b0044c130109a5d7b5fb5c317c02b4e28c1").await.unwrap();
+    }
+}
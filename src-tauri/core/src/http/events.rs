@@ -0,0 +1,67 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, State
+    },
+    response::Response,
+    routing::get,
+    Router
+};
+use log::{debug, warn};
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+use crate::http::ServerState;
+
+pub fn router() -> Router<ServerState> {
+    Router::new()
+        .route("/ws", get(all_events))
+        .route("/ws/{key}", get(instance_events))
+}
+
+async fn all_events(State(state): State<ServerState>, ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(move |socket| stream(socket, state, None))
+}
+
+async fn instance_events(
+    State(state): State<ServerState>,
+    Path(key): Path<Uuid>,
+    ws: WebSocketUpgrade
+) -> Response {
+    ws.on_upgrade(move |socket| stream(socket, state, Some(key)))
+}
+
+/// Forwards all events, or only the ones of the instance `key`, to the socket until it's closed.
+/// Each message is an [`crate::client::hooks::Event`] serialized as JSON.
+async fn stream(mut socket: WebSocket, state: ServerState, key: Option<Uuid>) {
+    let mut events = state.events.subscribe();
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if key.is_some_and(|key| key != event.key) {
+                        continue
+                    }
+                    let json = match serde_json::to_string(&event) {
+                        Ok(json) => json,
+                        Err(e) => {
+                            warn!("Failed to serialize event: {e}");
+                            continue
+                        }
+                    };
+                    if socket.send(Message::Text(json.into())).await.is_err() {
+                        break
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("WebSocket subscriber lagged behind, skipped {skipped} event(s)");
+                }
+                Err(RecvError::Closed) => break
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            }
+        }
+    }
+    debug!("WebSocket event stream closed");
+}
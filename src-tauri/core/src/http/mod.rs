@@ -0,0 +1,161 @@
+mod routes;
+mod events;
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    net::{Ipv4Addr, SocketAddr},
+    path::Path
+};
+use axum::{
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, StatusCode},
+    middleware::{self, Next},
+    response::Response,
+    Router
+};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use tokio::{net::TcpListener, task::JoinHandle};
+use uuid::Uuid;
+use crate::{
+    client::hooks::EventBroadcast,
+    AppState
+};
+
+/// Configuration of the local HTTP control API, stored in `http.json`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HttpConfig {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 4610
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Loads the config, writing the default one if there is none yet.
+    ///
+    /// # Errors
+    /// If the default config can't be written. A config that can't be read or parsed is
+    /// logged and replaced by the default one.
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        let path = path.join("http.json");
+        if !path.exists() {
+            fs::write(&path, serde_json::to_string_pretty(&Self::default())?)?;
+        }
+        let raw = fs::read_to_string(&path);
+        if let Ok(content) = raw {
+            match serde_json::from_str(content.as_str()) {
+                Ok(config) => return Ok(config),
+                Err(e) => error!("Failed to parse HTTP API config: {e}"),
+            }
+        }
+        error!("Failed to load HTTP API config from {path:?}");
+        Ok(Self::default())
+    }
+}
+
+/// Reads the bearer token required by the HTTP API from `http_token`,
+/// generating a new random one on first run, only readable by the current user.
+pub fn load_or_create_token(path: &Path) -> io::Result<String> {
+    let path = path.join("http_token");
+    if let Ok(token) = fs::read_to_string(&path) {
+        let token = token.trim();
+        if !token.is_empty() {
+            return Ok(token.to_string())
+        }
+    }
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    info!("Generated new HTTP API token at {path:?}");
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(&path)?;
+    // the mode only applies to new files, an empty one may already exist
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    file.write_all(token.as_bytes())?;
+    Ok(token)
+}
+
+#[derive(Clone)]
+struct ServerState {
+    app: AppState,
+    events: EventBroadcast,
+    token: String,
+}
+
+/// Compares both tokens in constant time.
+fn token_matches(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len() && expected.bytes()
+        .zip(provided.bytes())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Requires a valid bearer token on every request. As browsers can't set headers on
+/// WebSocket connections, the token can also be passed through the `token` query parameter.
+async fn authorize(
+    State(state): State<ServerState>,
+    request: Request,
+    next: Next
+) -> Result<Response, StatusCode> {
+    let header = request.headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let query = request.uri()
+        .query()
+        .and_then(|query| query.split('&').find_map(|pair| pair.strip_prefix("token=")));
+    match header.or(query) {
+        Some(token) if token_matches(&state.token, token) => Ok(next.run(request).await),
+        _ => Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+/// Starts the HTTP and WebSocket control API on `127.0.0.1:port`.
+///
+/// All routes mirror the Tauri commands, see [`routes`]. Instance events are streamed over
+/// WebSocket on `/ws` (all instances) and `/ws/{key}` (a single instance), the same events
+/// `events` receives from the event channel.
+pub async fn start(app: AppState, events: EventBroadcast, port: u16) -> io::Result<JoinHandle<()>> {
+    let token = {
+        let path = app.api_context.lock().unwrap().save.clone();
+        load_or_create_token(&path)?
+    };
+    let state = ServerState { app, events, token };
+    let router = Router::new()
+        .merge(routes::router())
+        .merge(events::router())
+        .layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .with_state(state);
+
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = TcpListener::bind(address).await?;
+    info!("HTTP API listening on http://{address}");
+    Ok(tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, router).await {
+            error!("HTTP API server stopped: {e}");
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::http::token_matches;
+
+    #[test]
+    fn token_comparison() {
+        assert!(token_matches("abcdef", "abcdef"));
+        assert!(!token_matches("abcdef", "abcdeg"));
+        assert!(!token_matches("abcdef", "abcde"));
+        assert!(!token_matches("abcdef", ""));
+    }
+}
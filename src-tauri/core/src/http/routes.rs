@@ -0,0 +1,553 @@
+use std::collections::HashMap;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Json, Router
+};
+use log::debug;
+use serde::Deserialize;
+use crate::{
+    api::{
+        self, CachedStatus, ClientConnection, ClientInfo, ClientworksError, Macro, MacroRunInfo,
+        MacroTarget, ProxyInfo, ServerInfo, Swarm,
+        auth::{AuthCacheState, AuthCredentials},
+        controller::InstanceStatus
+    },
+    client::{
+        auth::{AuthState, MinecraftProfile},
+        yggdrasil::YggdrasilServer,
+        hooks::{chatlog::ChatLine, logs::LogEntry},
+        movement::Movement,
+        tablist::TabList,
+        network::{ConnectionState, ReconnectPolicy},
+        Version
+    },
+    http::ServerState
+};
+
+// Where present, the ID and KEY path parameters represent the UUID of the client and controller, respectively.
+
+pub struct ApiError(ClientworksError);
+
+impl From<ClientworksError> for ApiError {
+    fn from(value: ClientworksError) -> Self {
+        Self(value)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        use ClientworksError::*;
+
+        let status = match &self.0 {
+            InvalidId { .. } | InvalidMacro { .. } | InvalidCommandPrefix { .. }
+            | InvalidVersion { .. } | InvalidProxy { .. } => StatusCode::BAD_REQUEST,
+            ClientNotFound { .. } | ServerNotFound { .. } | SwarmNotFound { .. }
+            | MacroNotFound { .. } | MacroRunNotFound { .. } | ScriptNotFound { .. }
+            | ProxyNotFound { .. } | InstanceNotFound { .. } | NoOngoingAuth { .. } => StatusCode::NOT_FOUND,
+            ClientExists { .. } | ServerExists { .. } | SwarmExists { .. }
+            | ProxyExists { .. } | ProxyPoolEmpty | InstanceOffline { .. } => StatusCode::CONFLICT,
+            ControllerMissing { .. } | AuthKeyMissing { .. } | AuthExpired { .. }
+            | AuthFailed { .. } | InvalidPassphrase { .. } | ProfileMissing => StatusCode::UNAUTHORIZED,
+            AuthCacheLocked => StatusCode::LOCKED,
+            InstanceTimeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+            ServerUnreachable { .. } => StatusCode::BAD_GATEWAY,
+            UnsupportedVersion { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            Io { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(self.0)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+fn client_found(value: Option<ClientInfo>, id: String) -> ApiResult<ClientInfo> {
+    value.map(Json).ok_or_else(|| ClientworksError::ClientNotFound { id }.into())
+}
+
+fn log_progress(state: &AuthState) {
+    debug!("{state}");
+}
+
+pub fn router() -> Router<ServerState> {
+    Router::new()
+        .route("/clients", get(get_clients))
+        .route("/clients/offline", post(auth_offline))
+        .route("/clients/by-username/{username}", get(get_client_by_user))
+        .route("/clients/{id}", get(get_client).delete(remove_client))
+        .route("/clients/{id}/authenticate", post(recall_authentication))
+        .route("/clients/{id}/proxy", put(set_client_proxy))
+        .route("/clients/{id}/validity", get(auth_validity))
+        .route("/clients/{id}/instances", get(get_instances).post(create_connection))
+        .route("/clients/{id}/instances/{key}/connect", post(connect_client))
+        .route("/clients/{id}/instances/{key}/disconnect", post(disconnect_client))
+        .route("/clients/{id}/instances/{key}/kill", post(kill_client))
+        .route("/clients/{id}/instances/{key}/kill-soft", post(kill_client_soft))
+        .route("/clients/{id}/instances/{key}/chat", post(send_chat))
+        .route("/clients/{id}/instances/{key}/movement", post(send_movement))
+        .route("/clients/{id}/instances/{key}/status", get(get_instance_status))
+        .route("/clients/{id}/instances/{key}/players", get(get_tab_list))
+        .route("/clients/{id}/instances/{key}/reconnect", put(set_reconnect_policy))
+        .route("/clients/{id}/instances/{key}/script", put(set_instance_script))
+        .route("/clients/{id}/instances/{key}/command-prefix", put(set_command_prefix))
+        .route("/clients/{id}/instances/{key}/proxy", put(set_instance_proxy))
+        .route("/clients/{id}/instances/{key}/logs", get(get_logs))
+        .route("/clients/{id}/instances/{key}/chat/since/{seq}", get(get_chat_since))
+        .route("/servers", get(get_servers).post(add_server))
+        .route("/servers/status", get(get_server_statuses))
+        .route("/servers/{name}/status", get(get_server_status))
+        .route("/servers/{name}", axum::routing::delete(delete_server))
+        .route("/proxies", get(get_proxies).post(add_proxy))
+        .route("/proxies/{name}", axum::routing::delete(delete_proxy))
+        .route("/swarms", get(get_swarms).post(create_swarm))
+        .route("/swarms/{name}", axum::routing::delete(delete_swarm))
+        .route("/swarms/{name}/proxy", put(set_swarm_proxy))
+        .route("/swarms/{name}/launch", post(launch_swarm))
+        .route("/swarms/{name}/disconnect", post(disconnect_swarm))
+        .route("/swarms/{name}/kill", post(kill_swarm))
+        .route("/macros", get(get_macros).post(save_macro))
+        .route("/macros/{name}", axum::routing::delete(delete_macro))
+        .route("/macros/{name}/run", post(start_macro))
+        .route("/macro-runs", get(get_macro_runs))
+        .route("/macro-runs/{run}", axum::routing::delete(cancel_macro))
+        .route("/scripts", get(get_scripts))
+        .route("/versions", get(get_available_versions))
+        .route("/auth/cache", get(get_auth_cache_state))
+        .route("/auth/cache/unlock", post(unlock_auth_cache))
+        .route("/auth/cache/passphrase", put(set_auth_passphrase))
+        .route("/auth/microsoft/{login_key}/init", post(auth_ms_init))
+        .route("/auth/microsoft/{login_key}/finish", post(auth_ms_finish))
+        .route("/auth/microsoft/{login_key}/cache", post(auth_ms_cache))
+        .route("/auth/yggdrasil/{login_key}", post(auth_yggdrasil))
+}
+
+async fn get_clients(State(state): State<ServerState>) -> Json<Vec<ClientInfo>> {
+    Json(api::get_clients(&state.app))
+}
+
+async fn get_client(State(state): State<ServerState>, Path(id): Path<String>) -> ApiResult<ClientInfo> {
+    client_found(api::get_client(&state.app, id.clone()), id)
+}
+
+async fn get_client_by_user(
+    State(state): State<ServerState>,
+    Path(username): Path<String>
+) -> ApiResult<ClientInfo> {
+    client_found(api::get_client_by_user(&state.app, username.clone()), username)
+}
+
+async fn remove_client(State(state): State<ServerState>, Path(uuid): Path<String>) -> ApiResult<()> {
+    Ok(Json(api::remove_client(&state.app, uuid)?))
+}
+
+#[derive(Deserialize)]
+struct OfflineRequest {
+    username: String
+}
+
+async fn auth_offline(
+    State(state): State<ServerState>,
+    Json(request): Json<OfflineRequest>
+) -> ApiResult<(String, MinecraftProfile)> {
+    Ok(Json(api::auth::auth_offline(&state.app, request.username, log_progress)?))
+}
+
+async fn recall_authentication(State(state): State<ServerState>, Path(id): Path<String>) -> ApiResult<bool> {
+    Ok(Json(api::auth::recall_authentication(&state.app, id, log_progress).await?))
+}
+
+/// The `{id}` segment is the Minecraft UUID of the account, not the ID of its client.
+async fn auth_validity(State(state): State<ServerState>, Path(uuid): Path<String>) -> ApiResult<u64> {
+    Ok(Json(api::auth::auth_validity(&state.app, uuid)?))
+}
+
+async fn get_instances(
+    State(state): State<ServerState>,
+    Path(id): Path<String>
+) -> ApiResult<HashMap<String, (ConnectionState, ClientConnection)>> {
+    Ok(Json(api::controller::get_instances(&state.app, id)?))
+}
+
+#[derive(Deserialize)]
+struct ConnectionRequest {
+    server_name: String,
+    version: String,
+    #[serde(default)]
+    proxy: Option<String>
+}
+
+async fn create_connection(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+    Json(request): Json<ConnectionRequest>
+) -> ApiResult<String> {
+    Ok(Json(api::controller::create_connection(
+        &state.app, id, request.server_name, request.version, request.proxy
+    ).await?))
+}
+
+async fn connect_client(
+    State(state): State<ServerState>,
+    Path((id, key)): Path<(String, String)>
+) -> ApiResult<()> {
+    Ok(Json(api::controller::connect_client(&state.app, id, key)?))
+}
+
+async fn disconnect_client(
+    State(state): State<ServerState>,
+    Path((id, key)): Path<(String, String)>
+) -> ApiResult<()> {
+    Ok(Json(api::controller::disconnect_client(&state.app, id, key).await?))
+}
+
+async fn kill_client(
+    State(state): State<ServerState>,
+    Path((id, key)): Path<(String, String)>
+) -> ApiResult<()> {
+    Ok(Json(api::controller::kill_client(&state.app, id, key).await?))
+}
+
+async fn kill_client_soft(
+    State(state): State<ServerState>,
+    Path((id, key)): Path<(String, String)>
+) -> ApiResult<()> {
+    Ok(Json(api::controller::kill_client_soft(&state.app, id, key).await?))
+}
+
+#[derive(Deserialize)]
+struct ChatRequest {
+    message: String
+}
+
+async fn send_chat(
+    State(state): State<ServerState>,
+    Path((id, key)): Path<(String, String)>,
+    Json(request): Json<ChatRequest>
+) -> ApiResult<()> {
+    Ok(Json(api::controller::send_chat(&state.app, id, key, request.message)?))
+}
+
+async fn get_instance_status(
+    State(state): State<ServerState>,
+    Path((id, key)): Path<(String, String)>
+) -> ApiResult<InstanceStatus> {
+    Ok(Json(api::controller::get_instance_status(&state.app, id, key)?))
+}
+
+async fn get_tab_list(
+    State(state): State<ServerState>,
+    Path((id, key)): Path<(String, String)>
+) -> ApiResult<TabList> {
+    Ok(Json(api::controller::get_tab_list(&state.app, id, key)?))
+}
+
+async fn send_movement(
+    State(state): State<ServerState>,
+    Path((id, key)): Path<(String, String)>,
+    Json(movement): Json<Movement>
+) -> ApiResult<()> {
+    Ok(Json(api::controller::send_movement(&state.app, id, key, movement)?))
+}
+
+async fn set_reconnect_policy(
+    State(state): State<ServerState>,
+    Path((id, key)): Path<(String, String)>,
+    Json(policy): Json<ReconnectPolicy>
+) -> ApiResult<()> {
+    Ok(Json(api::controller::set_reconnect_policy(&state.app, id, key, policy)?))
+}
+
+#[derive(Deserialize)]
+struct ScriptRequest {
+    script: Option<String>
+}
+
+async fn set_instance_script(
+    State(state): State<ServerState>,
+    Path((id, key)): Path<(String, String)>,
+    Json(request): Json<ScriptRequest>
+) -> ApiResult<()> {
+    Ok(Json(api::controller::set_instance_script(&state.app, id, key, request.script)?))
+}
+
+#[derive(Deserialize)]
+struct CommandPrefixRequest {
+    prefix: Option<String>
+}
+
+async fn set_command_prefix(
+    State(state): State<ServerState>,
+    Path((id, key)): Path<(String, String)>,
+    Json(request): Json<CommandPrefixRequest>
+) -> ApiResult<()> {
+    Ok(Json(api::controller::set_command_prefix(&state.app, id, key, request.prefix)?))
+}
+
+#[derive(Deserialize)]
+struct ProxyRequest {
+    proxy: Option<String>
+}
+
+async fn set_instance_proxy(
+    State(state): State<ServerState>,
+    Path((id, key)): Path<(String, String)>,
+    Json(request): Json<ProxyRequest>
+) -> ApiResult<()> {
+    Ok(Json(api::proxy::set_instance_proxy(&state.app, id, key, request.proxy)?))
+}
+
+async fn set_client_proxy(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+    Json(request): Json<ProxyRequest>
+) -> ApiResult<()> {
+    Ok(Json(api::proxy::set_client_proxy(&state.app, id, request.proxy)?))
+}
+
+async fn get_scripts(State(state): State<ServerState>) -> ApiResult<Vec<String>> {
+    Ok(Json(api::controller::get_scripts(&state.app)?))
+}
+
+async fn get_chat_since(
+    State(state): State<ServerState>,
+    Path((id, key, seq)): Path<(String, String, u64)>
+) -> ApiResult<Vec<ChatLine>> {
+    Ok(Json(api::controller::get_chat_since(&state.app, id, key, seq)?))
+}
+
+#[derive(Deserialize)]
+struct LogsQuery {
+    from: Option<i64>,
+    offset: Option<usize>,
+    to: Option<i64>,
+    limit: Option<usize>
+}
+
+async fn get_logs(
+    State(state): State<ServerState>,
+    Path((id, key)): Path<(String, String)>,
+    Query(query): Query<LogsQuery>
+) -> ApiResult<Vec<LogEntry>> {
+    Ok(Json(api::controller::get_logs(
+        &state.app, id, key, query.from, query.offset, query.to, query.limit
+    )?))
+}
+
+async fn get_servers(State(state): State<ServerState>) -> Json<Vec<ServerInfo>> {
+    Json(api::get_servers(&state.app))
+}
+
+#[derive(Deserialize)]
+struct StatusQuery {
+    #[serde(default)]
+    refresh: bool
+}
+
+async fn get_server_statuses(
+    State(state): State<ServerState>,
+    Query(query): Query<StatusQuery>
+) -> Json<Vec<CachedStatus>> {
+    Json(api::get_server_statuses(&state.app, query.refresh).await)
+}
+
+async fn get_server_status(
+    State(state): State<ServerState>,
+    Path(name): Path<String>,
+    Query(query): Query<StatusQuery>
+) -> ApiResult<CachedStatus> {
+    Ok(Json(api::get_server_status(&state.app, name, query.refresh).await?))
+}
+
+#[derive(Deserialize)]
+struct ServerRequest {
+    name: String,
+    ip: String,
+    port: u16
+}
+
+async fn add_server(
+    State(state): State<ServerState>,
+    Json(request): Json<ServerRequest>
+) -> ApiResult<()> {
+    Ok(Json(api::add_server(&state.app, request.name, request.ip, request.port)?))
+}
+
+async fn delete_server(State(state): State<ServerState>, Path(name): Path<String>) -> ApiResult<()> {
+    Ok(Json(api::delete_server(&state.app, name)?))
+}
+
+async fn get_proxies(State(state): State<ServerState>) -> Json<Vec<ProxyInfo>> {
+    Json(api::proxy::get_proxies(&state.app))
+}
+
+#[derive(Deserialize)]
+struct NewProxyRequest {
+    name: String,
+    url: String
+}
+
+async fn add_proxy(
+    State(state): State<ServerState>,
+    Json(request): Json<NewProxyRequest>
+) -> ApiResult<()> {
+    Ok(Json(api::proxy::add_proxy(&state.app, request.name, request.url)?))
+}
+
+async fn delete_proxy(State(state): State<ServerState>, Path(name): Path<String>) -> ApiResult<()> {
+    Ok(Json(api::proxy::delete_proxy(&state.app, name)?))
+}
+
+async fn get_swarms(State(state): State<ServerState>) -> Json<Vec<Swarm>> {
+    Json(api::swarm::get_swarms(&state.app))
+}
+
+#[derive(Deserialize)]
+struct SwarmRequest {
+    name: String,
+    clients: Vec<String>,
+    server_name: String,
+    version: String,
+    join_delay_ms: u64,
+    concurrency: usize
+}
+
+async fn create_swarm(
+    State(state): State<ServerState>,
+    Json(request): Json<SwarmRequest>
+) -> ApiResult<()> {
+    Ok(Json(api::swarm::create_swarm(
+        &state.app, request.name, request.clients, request.server_name,
+        request.version, request.join_delay_ms, request.concurrency
+    )?))
+}
+
+async fn set_swarm_proxy(
+    State(state): State<ServerState>,
+    Path(name): Path<String>,
+    Json(request): Json<ProxyRequest>
+) -> ApiResult<()> {
+    Ok(Json(api::swarm::set_swarm_proxy(&state.app, name, request.proxy)?))
+}
+
+async fn delete_swarm(State(state): State<ServerState>, Path(name): Path<String>) -> ApiResult<()> {
+    Ok(Json(api::swarm::delete_swarm(&state.app, name)?))
+}
+
+async fn launch_swarm(State(state): State<ServerState>, Path(name): Path<String>) -> ApiResult<Vec<String>> {
+    Ok(Json(api::swarm::launch_swarm(&state.app, name).await?))
+}
+
+async fn disconnect_swarm(State(state): State<ServerState>, Path(name): Path<String>) -> ApiResult<()> {
+    Ok(Json(api::swarm::disconnect_swarm(&state.app, name).await?))
+}
+
+async fn kill_swarm(State(state): State<ServerState>, Path(name): Path<String>) -> ApiResult<()> {
+    Ok(Json(api::swarm::kill_swarm(&state.app, name).await?))
+}
+
+async fn get_macros(State(state): State<ServerState>) -> Json<Vec<Macro>> {
+    Json(api::macros::get_macros(&state.app))
+}
+
+async fn save_macro(State(state): State<ServerState>, Json(request): Json<Macro>) -> ApiResult<()> {
+    Ok(Json(api::macros::save_macro(&state.app, request)?))
+}
+
+async fn delete_macro(State(state): State<ServerState>, Path(name): Path<String>) -> ApiResult<()> {
+    Ok(Json(api::macros::delete_macro(&state.app, name)?))
+}
+
+async fn start_macro(
+    State(state): State<ServerState>,
+    Path(name): Path<String>,
+    Json(targets): Json<Vec<MacroTarget>>
+) -> ApiResult<String> {
+    Ok(Json(api::macros::start_macro(&state.app, name, targets)?))
+}
+
+async fn get_macro_runs(State(state): State<ServerState>) -> Json<Vec<MacroRunInfo>> {
+    Json(api::macros::get_macro_runs(&state.app))
+}
+
+async fn cancel_macro(State(state): State<ServerState>, Path(run): Path<String>) -> ApiResult<()> {
+    Ok(Json(api::macros::cancel_macro(&state.app, run)?))
+}
+
+async fn get_available_versions() -> Json<Vec<Version>> {
+    Json(api::controller::get_available_versions())
+}
+
+async fn auth_ms_init(
+    State(state): State<ServerState>,
+    Path(login_key): Path<String>
+) -> ApiResult<AuthCredentials> {
+    Ok(Json(api::auth::auth_ms_init(&state.app, login_key, log_progress).await?))
+}
+
+#[derive(Deserialize)]
+struct FinishRequest {
+    register: bool
+}
+
+async fn auth_ms_finish(
+    State(state): State<ServerState>,
+    Path(login_key): Path<String>,
+    Json(request): Json<FinishRequest>
+) -> ApiResult<(String, MinecraftProfile)> {
+    Ok(Json(api::auth::auth_ms_finish(&state.app, login_key, request.register, log_progress).await?))
+}
+
+async fn auth_ms_cache(
+    State(state): State<ServerState>,
+    Path(login_key): Path<String>
+) -> ApiResult<(String, MinecraftProfile)> {
+    Ok(Json(api::auth::auth_ms_cache(&state.app, login_key, log_progress).await?))
+}
+
+#[derive(Deserialize)]
+struct YggdrasilRequest {
+    server: YggdrasilServer,
+    username: String,
+    password: String
+}
+
+async fn auth_yggdrasil(
+    State(state): State<ServerState>,
+    Path(login_key): Path<String>,
+    Json(request): Json<YggdrasilRequest>
+) -> ApiResult<(String, MinecraftProfile)> {
+    Ok(Json(api::auth::auth_yggdrasil(
+        &state.app, login_key, request.server, request.username, request.password, log_progress
+    ).await?))
+}
+
+async fn get_auth_cache_state(State(state): State<ServerState>) -> Json<AuthCacheState> {
+    Json(api::auth::get_auth_cache_state(&state.app))
+}
+
+#[derive(Deserialize)]
+struct UnlockRequest {
+    passphrase: String
+}
+
+async fn unlock_auth_cache(
+    State(state): State<ServerState>,
+    Json(request): Json<UnlockRequest>
+) -> ApiResult<()> {
+    Ok(Json(api::auth::unlock_auth_cache(&state.app, request.passphrase)?))
+}
+
+#[derive(Deserialize)]
+struct PassphraseRequest {
+    current: Option<String>,
+    new: Option<String>
+}
+
+async fn set_auth_passphrase(
+    State(state): State<ServerState>,
+    Json(request): Json<PassphraseRequest>
+) -> ApiResult<()> {
+    Ok(Json(api::auth::set_auth_passphrase(&state.app, request.current, request.new)?))
+}
@@ -0,0 +1,62 @@
+//! The core of Clientworks: client controllers and instances, authentication and the
+//! persisted client, server and authentication lists, independent of any frontend.
+//!
+//! Instance events are delivered to an [`EventSink`], which frontends implement to
+//! display or forward them. [`AppState`] bundles everything needed to drive the command
+//! layer in [`api`].
+
+pub mod api;
+pub mod client;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(test)]
+mod test_support;
+
+use std::{
+    fs, path::PathBuf,
+    sync::{Mutex, Arc}
+};
+
+pub use api::{
+    ApiContext, Client, ClientConnection, ClientList, ClientworksError, Server, ServerList,
+    Swarm, SwarmList, Macro, MacroList,
+    auth::AuthCache
+};
+pub use client::{
+    AuthProtocol, ClientController, ClientInstance, ControllerContainer, Version,
+    auth::Authentication,
+    hooks::{Event, EventBroadcast, EventSink, MacroStatus, Payload},
+    network::{ConnectionError, ConnectionState, DisconnectReason, ReconnectPolicy}
+};
+
+/// The identifier of the application, also used as the name of the data directory.
+pub const APP_IDENTIFIER: &str = "herr.clientworks.client";
+
+#[derive(Clone)]
+pub struct AppState {
+    pub com_channel: Arc<Mutex<client::hooks::Channel>>,
+    pub api_context: Arc<Mutex<ApiContext>>,
+}
+
+impl AppState {
+    /// Loads the app data from the given directory and starts the event channel,
+    /// forwarding all instance events to `sink` and to the instance logs.
+    pub fn load<S: EventSink>(path: PathBuf, sink: S) -> Self {
+        fs::create_dir_all(&path)
+            .expect(format!("Failed to create data directory at: {}",
+                            path.display()).as_str()
+            );
+        let logs = client::hooks::logs::LogSink::new(path.join(client::hooks::logs::LOGS_DIR));
+        let com_channel = client::hooks::init((logs, sink));
+        let api_context = api::load_from_dir(path, com_channel.sender.clone());
+        Self {
+            com_channel: Arc::new(Mutex::new(com_channel)),
+            api_context: Arc::new(Mutex::new(api_context))
+        }
+    }
+}
+
+/// The default data directory, matching the one Tauri resolves for the desktop app.
+pub fn default_data_dir() -> PathBuf {
+    dirs::data_dir().unwrap_or_default().join(APP_IDENTIFIER)
+}
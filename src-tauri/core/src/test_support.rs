@@ -0,0 +1,128 @@
+//! Local stand-ins for the servers tests talk to, shared by the unit tests and, through a
+//! `#[path]` module, by the integration tests.
+
+use std::{future::Future, net::SocketAddr};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle
+};
+
+/// A request received by an HTTP stand-in.
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    /// The header lines, lowercased
+    pub headers: String,
+    pub body: String,
+}
+
+impl HttpRequest {
+    /// The body parsed as JSON, `Null` if it isn't.
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_str(&self.body).unwrap_or_default()
+    }
+}
+
+/// Reads an HTTP request, `None` if the connection closes first.
+pub async fn read_request(stream: &mut TcpStream) -> Option<HttpRequest> {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    loop {
+        let len = stream.read(&mut buf).await.ok()?;
+        if len == 0 {
+            return None
+        }
+        request.extend_from_slice(&buf[..len]);
+        let text = String::from_utf8_lossy(&request).to_string();
+        let Some((head, body)) = text.split_once("\r\n\r\n") else {
+            continue
+        };
+        let headers = head.to_lowercase();
+        let length: usize = headers.lines()
+            .find_map(|line| line.strip_prefix("content-length: "))
+            .map_or(0, |length| length.parse().unwrap());
+        if body.len() >= length {
+            let mut line = head.split(' ');
+            return Some(HttpRequest {
+                method: line.next()?.to_string(),
+                path: line.next()?.to_string(),
+                headers,
+                body: body.to_string()
+            })
+        }
+    }
+}
+
+/// Writes a JSON response, closing the connection after it.
+pub async fn write_response(stream: &mut TcpStream, status: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+/// A server listening on a random local port, stopped when dropped.
+pub struct StandIn {
+    addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl StandIn {
+    /// Serves the first connection with `serve`, then stops listening.
+    pub async fn once<F, Fut>(serve: F) -> Self
+    where
+        F: FnOnce(TcpStream) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            serve(stream).await;
+        });
+        Self { addr, task }
+    }
+
+    /// Answers every HTTP request with the status and JSON body returned by `respond`.
+    pub async fn http<F>(mut respond: F) -> Self
+    where
+        F: FnMut(&HttpRequest) -> (&'static str, String) + Send + 'static
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let task = tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let Some(request) = read_request(&mut stream).await else {
+                    continue
+                };
+                let (status, body) = respond(&request);
+                write_response(&mut stream, status, &body).await;
+            }
+        });
+        Self { addr, task }
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The base URL of an HTTP stand-in, e.g. `http://127.0.0.1:41234`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Waits for a stand-in started with [`StandIn::once`] to be done, failing if any of its
+    /// assertions did.
+    pub async fn finish(mut self) {
+        (&mut self.task).await.unwrap();
+    }
+}
+
+impl Drop for StandIn {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
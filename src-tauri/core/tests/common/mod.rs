@@ -0,0 +1,142 @@
+//! A stand-in for the Microsoft services the authentication goes through: the device code
+//! flow of Microsoft accounts, Xbox Live, XSTS and the Minecraft services.
+
+#[allow(dead_code)]
+#[path = "../../src/test_support.rs"]
+mod test_support;
+
+use std::sync::{Arc, Mutex};
+use clientworks_core::client::auth::Endpoints;
+use test_support::{HttpRequest, StandIn};
+
+pub const USER_CODE: &str = "ABCD-1234";
+pub const MSA_TOKEN: &str = "msa-access";
+pub const REFRESHED_MSA_TOKEN: &str = "msa-access-refreshed";
+pub const MINECRAFT_TOKEN: &str = "minecraft-access";
+pub const PROFILE_ID: &str = "8667ba71b85a4004af54457a9734eed7";
+pub const PROFILE_NAME: &str = "Steve";
+
+const XBL_TOKEN: &str = "xbl-token";
+const XSTS_TOKEN: &str = "xsts-token";
+const USER_HASH: &str = "user-hash";
+
+/// How the stand-in behaves.
+#[derive(Clone, Debug)]
+pub struct Behavior {
+    /// How many polls for the MSA token are answered as pending before the user "enters" the code
+    pub pending_polls: usize,
+    /// Whether the user ever enters the code
+    pub approve: bool,
+    /// Whether refresh tokens are rejected
+    pub reject_refresh: bool,
+    /// Whether the account owns Minecraft
+    pub has_profile: bool,
+}
+
+impl Default for Behavior {
+    fn default() -> Self {
+        Self {
+            pending_polls: 2,
+            approve: true,
+            reject_refresh: false,
+            has_profile: true
+        }
+    }
+}
+
+pub struct MockMicrosoft {
+    pub endpoints: Endpoints,
+    requests: Arc<Mutex<Vec<String>>>,
+    /// Stopped along with the mock
+    _stand_in: StandIn,
+}
+
+impl MockMicrosoft {
+    pub async fn start(behavior: Behavior) -> Self {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let log = requests.clone();
+        let mut polls = 0;
+        let stand_in = StandIn::http(move |request| {
+            log.lock().unwrap().push(request.path.clone());
+            respond(&behavior, &mut polls, request)
+        }).await;
+        let endpoints = Endpoints::with_base(&stand_in.url());
+        Self { endpoints, requests, _stand_in: stand_in }
+    }
+
+    /// How many requests were made to `path`.
+    pub fn requests(&self, path: &str) -> usize {
+        self.requests.lock().unwrap().iter().filter(|p| *p == path).count()
+    }
+}
+
+fn token_response(access_token: &str) -> String {
+    format!(
+        r#"{{"token_type":"bearer","expires_in":86400,"scope":"service::user.auth.xboxlive.com::MBI_SSL","access_token":"{access_token}","refresh_token":"msa-refresh","user_id":"user"}}"#
+    )
+}
+
+fn xbox_response(token: &str) -> String {
+    format!(
+        r#"{{"IssueInstant":"2026-01-01T00:00:00Z","NotAfter":"2026-01-02T00:00:00Z","Token":"{token}","DisplayClaims":{{"xui":[{{"uhs":"{USER_HASH}"}}]}}}}"#
+    )
+}
+
+fn oauth_error(error: &str, description: &str) -> String {
+    format!(r#"{{"error":"{error}","error_description":"{description}"}}"#)
+}
+
+fn respond(behavior: &Behavior, polls: &mut usize, request: &HttpRequest) -> (&'static str, String) {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/oauth20_connect.srf") => {
+            assert!(request.body.contains("response_type=device_code"));
+            ("200 OK", format!(
+                r#"{{"user_code":"{USER_CODE}","device_code":"device","verification_uri":"https://www.microsoft.com/link","expires_in":900,"interval":0}}"#
+            ))
+        }
+        ("POST", "/oauth20_token.srf") if request.body.contains("grant_type=refresh_token") => {
+            assert!(request.body.contains("refresh_token=msa-refresh"));
+            if behavior.reject_refresh {
+                ("400 Bad Request", oauth_error("invalid_grant", "The refresh token has expired."))
+            } else {
+                ("200 OK", token_response(REFRESHED_MSA_TOKEN))
+            }
+        }
+        ("POST", "/oauth20_token.srf") => {
+            assert!(request.body.contains("device_code=device"));
+            *polls += 1;
+            if behavior.approve && *polls > behavior.pending_polls {
+                ("200 OK", token_response(MSA_TOKEN))
+            } else {
+                ("400 Bad Request", oauth_error("authorization_pending", "The user hasn't entered the code yet."))
+            }
+        }
+        ("POST", "/user/authenticate") => {
+            let ticket = request.json()["Properties"]["RpsTicket"].as_str().unwrap_or_default().to_string();
+            if ticket == MSA_TOKEN || ticket == REFRESHED_MSA_TOKEN {
+                ("200 OK", xbox_response(XBL_TOKEN))
+            } else {
+                ("401 Unauthorized", String::new())
+            }
+        }
+        ("POST", "/xsts/authorize") => {
+            assert_eq!(request.json()["Properties"]["UserTokens"][0], XBL_TOKEN);
+            ("200 OK", xbox_response(XSTS_TOKEN))
+        }
+        ("POST", "/authentication/login_with_xbox") => {
+            assert_eq!(request.json()["identityToken"], format!("XBL3.0 x={USER_HASH};{XSTS_TOKEN}"));
+            ("200 OK", format!(
+                r#"{{"username":"{PROFILE_ID}","roles":[],"access_token":"{MINECRAFT_TOKEN}","token_type":"Bearer","expires_in":86400}}"#
+            ))
+        }
+        ("GET", "/minecraft/profile") => {
+            assert!(request.headers.contains(&format!("bearer {}", MINECRAFT_TOKEN.to_lowercase())));
+            if behavior.has_profile {
+                ("200 OK", format!(r#"{{"id":"{PROFILE_ID}","name":"{PROFILE_NAME}","skins":[],"capes":[]}}"#))
+            } else {
+                ("404 Not Found", r#"{"path":"/minecraft/profile","errorType":"NOT_FOUND","error":"NOT_FOUND"}"#.to_string())
+            }
+        }
+        _ => ("404 Not Found", String::new())
+    }
+}
@@ -0,0 +1,83 @@
+//! The Microsoft authentication flow against a stand-in for the Microsoft services, see
+//! [`common::MockMicrosoft`].
+
+mod common;
+
+use std::time::Duration;
+use clientworks_core::client::auth::{self, AuthState, AuthTimeout, Authentication};
+use common::{Behavior, MockMicrosoft};
+use uuid::Uuid;
+
+async fn authenticate(mock: &MockMicrosoft, timeout: Duration) -> Authentication {
+    let mut auth = Authentication::with_client(reqwest::Client::new(), mock.endpoints.clone());
+    auth.get_access_info(|_| {}).await;
+    auth.authenticate_ms(AuthTimeout::from(timeout), |_| {}).await;
+    auth
+}
+
+#[tokio::test]
+async fn full_process() {
+    let mock = MockMicrosoft::start(Behavior::default()).await;
+    let mut states = Vec::new();
+    let mut auth = authenticate(&mock, Duration::from_secs(10)).await;
+    assert_eq!(auth.credentials.as_ref().unwrap().code, common::USER_CODE);
+    assert_eq!(auth.msa.as_ref().unwrap().data.access_token, common::MSA_TOKEN);
+    // answered as pending twice before the code is entered
+    assert_eq!(mock.requests("/oauth20_token.srf"), 3);
+
+    auth.authenticate_minecraft(|state| states.push(state.clone())).await;
+    assert!(auth.state_is_final());
+    assert_eq!(auth.state, AuthState::Success(common::MINECRAFT_TOKEN.to_string()));
+    assert_eq!(states.last(), Some(&auth.state));
+    assert_eq!(auth.access_token.as_ref().unwrap().access_token, common::MINECRAFT_TOKEN);
+    let profile = auth.profile.unwrap();
+    assert_eq!(profile.username, common::PROFILE_NAME);
+    assert_eq!(profile.uuid, Uuid::parse_str(common::PROFILE_ID).unwrap());
+    assert!(profile.authenticated);
+
+    let client = reqwest::Client::new();
+    let (msa, token) = auth::refresh_session(|_| {}, &client, &mock.endpoints, auth.msa.as_ref().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(msa.data.access_token, common::REFRESHED_MSA_TOKEN);
+    assert_eq!(token.access_token, common::MINECRAFT_TOKEN);
+}
+
+#[tokio::test]
+async fn timeout() {
+    let mock = MockMicrosoft::start(Behavior { approve: false, ..Default::default() }).await;
+    let auth = authenticate(&mock, Duration::from_millis(500)).await;
+    assert!(auth.state_is_final());
+    assert_eq!(auth.state, AuthState::Error("Authentication timed out".to_string()));
+    assert!(auth.msa.is_none());
+    assert!(mock.requests("/oauth20_token.srf") >= 1);
+}
+
+#[tokio::test]
+async fn refresh_failure() {
+    let mock = MockMicrosoft::start(Behavior { reject_refresh: true, ..Default::default() }).await;
+    let auth = authenticate(&mock, Duration::from_secs(10)).await;
+    let msa = auth.msa.unwrap();
+
+    let mut states = Vec::new();
+    let client = reqwest::Client::new();
+    let error = auth::refresh_session(|state| states.push(state.clone()), &client, &mock.endpoints, &msa)
+        .await
+        .unwrap_err();
+    assert_eq!(error, "The refresh token has expired.");
+    assert!(matches!(states.last(), Some(AuthState::Error(message)) if message.contains("Re-authentication is required")));
+    // no session token is requested without a valid MSA token
+    assert_eq!(mock.requests("/authentication/login_with_xbox"), 0);
+}
+
+#[tokio::test]
+async fn profile_missing() {
+    let mock = MockMicrosoft::start(Behavior { has_profile: false, ..Default::default() }).await;
+    let mut auth = authenticate(&mock, Duration::from_secs(10)).await;
+    auth.authenticate_minecraft(|_| {}).await;
+    assert!(auth.state_is_final());
+    assert_eq!(auth.state, AuthState::Error("The account doesn't own Minecraft".to_string()));
+    assert_eq!(auth.access_token.unwrap().access_token, common::MINECRAFT_TOKEN);
+    assert!(auth.profile.is_none());
+    assert_eq!(mock.requests("/minecraft/profile"), 1);
+}
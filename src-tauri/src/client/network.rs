@@ -1,12 +0,0 @@
-use crate::api::Server;
-
-pub enum ConnectionStatus {
-    Connected,
-    Disconnected,
-}
-
-pub struct ConnectionHandle {
-    pub server: Server,
-    pub status: ConnectionStatus,
-    
-}
\ No newline at end of file
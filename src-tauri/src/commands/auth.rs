@@ -0,0 +1,114 @@
+use tauri::{AppHandle, Emitter, State};
+use clientworks_core::{
+    api::{
+        self, ClientworksError,
+        auth::{AuthCacheState, AuthCredentials, AuthProgress}
+    },
+    client::{
+        auth::{AuthState, MinecraftProfile},
+        yggdrasil::YggdrasilServer
+    },
+    AppState
+};
+
+fn emit_progress_event(app: &AppHandle, state: &AuthState) {
+    let progress = AuthProgress::from(state);
+    app.emit("auth-progress-update", progress);
+}
+
+#[tauri::command]
+pub fn get_auth_cache_state(ctx: State<'_, AppState>) -> AuthCacheState {
+    api::auth::get_auth_cache_state(&ctx)
+}
+
+#[tauri::command]
+pub fn unlock_auth_cache(ctx: State<'_, AppState>, passphrase: String) -> Result<(), ClientworksError> {
+    api::auth::unlock_auth_cache(&ctx, passphrase)
+}
+
+#[tauri::command]
+pub fn set_auth_passphrase(
+    ctx: State<'_, AppState>,
+    current: Option<String>,
+    new: Option<String>
+) -> Result<(), ClientworksError> {
+    api::auth::set_auth_passphrase(&ctx, current, new)
+}
+
+#[tauri::command]
+pub fn auth_validity(
+    ctx: State<'_, AppState>,
+    uuid: String
+) -> Result<u64, ClientworksError> {
+    api::auth::auth_validity(&ctx, uuid)
+}
+
+#[tauri::command]
+pub async fn recall_authentication(
+    app: AppHandle,
+    ctx: State<'_, AppState>,
+    id: String
+) -> Result<bool, ClientworksError> {
+    api::auth::recall_authentication(&ctx, id, |state| {
+        emit_progress_event(&app, state);
+    }).await
+}
+
+#[tauri::command]
+pub async fn auth_offline(
+    app: AppHandle,
+    ctx: State<'_, AppState>,
+    username: String
+) -> Result<(String, MinecraftProfile), ClientworksError> {
+    api::auth::auth_offline(&ctx, username, |state| {
+        emit_progress_event(&app, state);
+    })
+}
+
+#[tauri::command]
+pub async fn auth_ms_cache(
+    app: AppHandle,
+    ctx: State<'_, AppState>,
+    login_key: String,
+) -> Result<(String, MinecraftProfile), ClientworksError> {
+    api::auth::auth_ms_cache(&ctx, login_key, |state| {
+        emit_progress_event(&app, state);
+    }).await
+}
+
+#[tauri::command]
+pub async fn auth_yggdrasil(
+    app: AppHandle,
+    ctx: State<'_, AppState>,
+    login_key: String,
+    server: YggdrasilServer,
+    username: String,
+    password: String
+) -> Result<(String, MinecraftProfile), ClientworksError> {
+    api::auth::auth_yggdrasil(&ctx, login_key, server, username, password, |state| {
+        emit_progress_event(&app, state);
+    }).await
+}
+
+#[tauri::command]
+pub async fn auth_ms_init(
+    app: AppHandle,
+    ctx: State<'_, AppState>,
+    login_key: String,
+) -> Result<AuthCredentials, ClientworksError> {
+    api::auth::auth_ms_init(&ctx, login_key, |state| {
+        emit_progress_event(&app, state);
+    }).await
+}
+
+#[tauri::command]
+pub async fn auth_ms_finish(
+    app: AppHandle,
+    ctx: State<'_, AppState>,
+    login_key: String,
+    register: bool
+) -> Result<(String, MinecraftProfile), ClientworksError> {
+    api::auth::auth_ms_finish(&ctx, login_key, register, |state| {
+        emit_progress_event(&app, state);
+    }).await
+}
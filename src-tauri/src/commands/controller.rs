@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use tauri::State;
+use clientworks_core::{
+    api::{self, ClientConnection, ClientworksError, controller::InstanceStatus},
+    client::{
+        hooks::{chatlog::ChatLine, logs::LogEntry},
+        movement::Movement,
+        tablist::TabList,
+        network::{ConnectionState, ReconnectPolicy},
+        Version
+    },
+    AppState
+};
+// Where present, the ID and KEY parameters represent the UUID of the client and controller, respectively.
+
+#[tauri::command]
+pub async fn create_connection(
+    ctx: State<'_, AppState>,
+    id: String,
+    server_name: String,
+    version: String,
+    proxy: Option<String>
+) -> Result<String, ClientworksError> {
+    api::controller::create_connection(&ctx, id, server_name, version, proxy).await
+}
+
+#[tauri::command]
+pub fn get_instances(
+    ctx: State<'_, AppState>,
+    id: String
+) -> Result<HashMap<String, (ConnectionState, ClientConnection)>, ClientworksError> {
+    api::controller::get_instances(&ctx, id)
+}
+
+#[tauri::command]
+pub fn get_available_versions() -> Vec<Version> {
+    api::controller::get_available_versions()
+}
+
+#[tauri::command]
+pub fn send_chat(
+    ctx: State<'_, AppState>,
+    id: String, key: String,
+    message: String
+) -> Result<(), ClientworksError> {
+    api::controller::send_chat(&ctx, id, key, message)
+}
+
+#[tauri::command]
+pub fn get_instance_status(
+    ctx: State<'_, AppState>,
+    id: String, key: String
+) -> Result<InstanceStatus, ClientworksError> {
+    api::controller::get_instance_status(&ctx, id, key)
+}
+
+#[tauri::command]
+pub fn get_tab_list(
+    ctx: State<'_, AppState>,
+    id: String, key: String
+) -> Result<TabList, ClientworksError> {
+    api::controller::get_tab_list(&ctx, id, key)
+}
+
+#[tauri::command]
+pub fn send_movement(
+    ctx: State<'_, AppState>,
+    id: String, key: String,
+    movement: Movement
+) -> Result<(), ClientworksError> {
+    api::controller::send_movement(&ctx, id, key, movement)
+}
+
+#[tauri::command]
+pub fn set_reconnect_policy(
+    ctx: State<'_, AppState>,
+    id: String, key: String,
+    policy: ReconnectPolicy
+) -> Result<(), ClientworksError> {
+    api::controller::set_reconnect_policy(&ctx, id, key, policy)
+}
+
+#[tauri::command]
+pub fn set_instance_script(
+    ctx: State<'_, AppState>,
+    id: String, key: String,
+    script: Option<String>
+) -> Result<(), ClientworksError> {
+    api::controller::set_instance_script(&ctx, id, key, script)
+}
+
+#[tauri::command]
+pub fn set_command_prefix(
+    ctx: State<'_, AppState>,
+    id: String, key: String,
+    prefix: Option<String>
+) -> Result<(), ClientworksError> {
+    api::controller::set_command_prefix(&ctx, id, key, prefix)
+}
+
+#[tauri::command]
+pub fn get_scripts(ctx: State<'_, AppState>) -> Result<Vec<String>, ClientworksError> {
+    api::controller::get_scripts(&ctx)
+}
+
+#[tauri::command]
+pub fn get_chat_since(
+    ctx: State<'_, AppState>,
+    id: String, key: String,
+    seq: u64
+) -> Result<Vec<ChatLine>, ClientworksError> {
+    api::controller::get_chat_since(&ctx, id, key, seq)
+}
+
+#[tauri::command]
+pub fn get_logs(
+    ctx: State<'_, AppState>,
+    id: String, key: String,
+    from: Option<i64>, offset: Option<usize>, to: Option<i64>,
+    limit: Option<usize>
+) -> Result<Vec<LogEntry>, ClientworksError> {
+    api::controller::get_logs(&ctx, id, key, from, offset, to, limit)
+}
+
+#[tauri::command]
+pub fn connect_client(
+    ctx: State<'_, AppState>,
+    id: String, key: String
+) -> Result<(), ClientworksError> {
+    api::controller::connect_client(&ctx, id, key)
+}
+
+#[tauri::command]
+pub async fn disconnect_client(
+    ctx: State<'_, AppState>,
+    id: String, key: String
+) -> Result<(), ClientworksError> {
+    api::controller::disconnect_client(&ctx, id, key).await
+}
+
+#[tauri::command]
+pub async fn kill_client_soft(
+    ctx: State<'_, AppState>,
+    id: String, key: String
+) -> Result<(), ClientworksError> {
+    api::controller::kill_client_soft(&ctx, id, key).await
+}
+
+#[tauri::command]
+pub async fn kill_client(
+    ctx: State<'_, AppState>,
+    id: String, key: String
+) -> Result<(), ClientworksError> {
+    api::controller::kill_client(&ctx, id, key).await
+}
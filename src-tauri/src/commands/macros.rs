@@ -0,0 +1,39 @@
+use tauri::State;
+use clientworks_core::{
+    api::{self, ClientworksError, Macro, MacroRunInfo, MacroTarget},
+    AppState
+};
+
+#[tauri::command]
+pub fn get_macros(ctx: State<'_, AppState>) -> Vec<Macro> {
+    api::macros::get_macros(&ctx)
+}
+
+#[tauri::command]
+pub fn save_macro(ctx: State<'_, AppState>, definition: Macro) -> Result<(), ClientworksError> {
+    api::macros::save_macro(&ctx, definition)
+}
+
+#[tauri::command]
+pub fn delete_macro(ctx: State<'_, AppState>, name: String) -> Result<(), ClientworksError> {
+    api::macros::delete_macro(&ctx, name)
+}
+
+#[tauri::command]
+pub fn start_macro(
+    ctx: State<'_, AppState>,
+    name: String,
+    targets: Vec<MacroTarget>
+) -> Result<String, ClientworksError> {
+    api::macros::start_macro(&ctx, name, targets)
+}
+
+#[tauri::command]
+pub fn cancel_macro(ctx: State<'_, AppState>, run: String) -> Result<(), ClientworksError> {
+    api::macros::cancel_macro(&ctx, run)
+}
+
+#[tauri::command]
+pub fn get_macro_runs(ctx: State<'_, AppState>) -> Vec<MacroRunInfo> {
+    api::macros::get_macro_runs(&ctx)
+}
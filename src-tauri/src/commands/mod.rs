@@ -0,0 +1,90 @@
+use log::{debug, error};
+use tauri::{AppHandle, Emitter, State};
+use clientworks_core::{
+    api::{self, CachedStatus, ClientInfo, ClientworksError, ServerInfo},
+    AppState, Event, EventSink
+};
+
+pub mod auth;
+pub mod controller;
+pub mod macros;
+pub mod proxy;
+pub mod swarm;
+
+// Thin Tauri wrappers over the command layer in `crate::api`.
+
+/// Emits all instance events to the frontend, using the instance UUID as event name.
+pub struct TauriSink(pub AppHandle);
+
+impl EventSink for TauriSink {
+    fn emit_event(&self, event: &Event) {
+        match self.0.emit(event.key.to_string().as_str(), event.payload.clone()) {
+            Ok(_) => {
+                debug!("Event emitted for: {}", event.key);
+            },
+            Err(err) => {
+                error!("Error emitting event: {err}");
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub fn remove_client(ctx: State<'_, AppState>, uuid: String) -> Result<(), ClientworksError> {
+    api::remove_client(&ctx, uuid)
+}
+
+#[tauri::command]
+pub fn get_client(ctx: State<'_, AppState>, id: String) -> Option<ClientInfo> {
+    api::get_client(&ctx, id)
+}
+
+#[tauri::command]
+pub fn get_client_by_user(
+    ctx: State<'_, AppState>,
+    username: String,
+) -> Option<ClientInfo> {
+    api::get_client_by_user(&ctx, username)
+}
+
+#[tauri::command]
+pub fn get_clients(ctx: State<'_, AppState>) -> Vec<ClientInfo> {
+    api::get_clients(&ctx)
+}
+
+#[tauri::command]
+pub fn add_server(
+    ctx: State<'_, AppState>,
+    name: String,
+    ip: String,
+    port: u16,
+) -> Result<(), ClientworksError> {
+    api::add_server(&ctx, name, ip, port)
+}
+
+#[tauri::command]
+pub fn delete_server(ctx: State<'_, AppState>, name: String) -> Result<(), ClientworksError> {
+    api::delete_server(&ctx, name)
+}
+
+#[tauri::command]
+pub fn get_servers(ctx: State<'_, AppState>) -> Vec<ServerInfo> {
+    api::get_servers(&ctx)
+}
+
+#[tauri::command]
+pub async fn get_server_status(
+    ctx: State<'_, AppState>,
+    name: String,
+    refresh: bool
+) -> Result<CachedStatus, ClientworksError> {
+    api::get_server_status(&ctx, name, refresh).await
+}
+
+#[tauri::command]
+pub async fn get_server_statuses(
+    ctx: State<'_, AppState>,
+    refresh: bool
+) -> Result<Vec<CachedStatus>, ClientworksError> {
+    Ok(api::get_server_statuses(&ctx, refresh).await)
+}
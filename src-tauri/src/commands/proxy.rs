@@ -0,0 +1,39 @@
+use tauri::State;
+use clientworks_core::{
+    api::{self, ClientworksError, ProxyInfo},
+    AppState
+};
+
+#[tauri::command]
+pub fn get_proxies(ctx: State<'_, AppState>) -> Vec<ProxyInfo> {
+    api::proxy::get_proxies(&ctx)
+}
+
+#[tauri::command]
+pub fn add_proxy(ctx: State<'_, AppState>, name: String, url: String) -> Result<(), ClientworksError> {
+    api::proxy::add_proxy(&ctx, name, url)
+}
+
+#[tauri::command]
+pub fn delete_proxy(ctx: State<'_, AppState>, name: String) -> Result<(), ClientworksError> {
+    api::proxy::delete_proxy(&ctx, name)
+}
+
+#[tauri::command]
+pub fn set_client_proxy(
+    ctx: State<'_, AppState>,
+    id: String,
+    proxy: Option<String>
+) -> Result<(), ClientworksError> {
+    api::proxy::set_client_proxy(&ctx, id, proxy)
+}
+
+#[tauri::command]
+pub fn set_instance_proxy(
+    ctx: State<'_, AppState>,
+    id: String,
+    key: String,
+    proxy: Option<String>
+) -> Result<(), ClientworksError> {
+    api::proxy::set_instance_proxy(&ctx, id, key, proxy)
+}
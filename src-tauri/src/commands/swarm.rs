@@ -0,0 +1,48 @@
+use tauri::State;
+use clientworks_core::{
+    api::{self, ClientworksError, Swarm},
+    AppState
+};
+
+#[tauri::command]
+pub fn get_swarms(ctx: State<'_, AppState>) -> Vec<Swarm> {
+    api::swarm::get_swarms(&ctx)
+}
+
+#[tauri::command]
+pub fn create_swarm(
+    ctx: State<'_, AppState>,
+    name: String,
+    clients: Vec<String>,
+    server_name: String,
+    version: String,
+    join_delay_ms: u64,
+    concurrency: usize
+) -> Result<(), ClientworksError> {
+    api::swarm::create_swarm(&ctx, name, clients, server_name, version, join_delay_ms, concurrency)
+}
+
+#[tauri::command]
+pub fn set_swarm_proxy(ctx: State<'_, AppState>, name: String, proxy: Option<String>) -> Result<(), ClientworksError> {
+    api::swarm::set_swarm_proxy(&ctx, name, proxy)
+}
+
+#[tauri::command]
+pub fn delete_swarm(ctx: State<'_, AppState>, name: String) -> Result<(), ClientworksError> {
+    api::swarm::delete_swarm(&ctx, name)
+}
+
+#[tauri::command]
+pub async fn launch_swarm(ctx: State<'_, AppState>, name: String) -> Result<Vec<String>, ClientworksError> {
+    api::swarm::launch_swarm(&ctx, name).await
+}
+
+#[tauri::command]
+pub async fn disconnect_swarm(ctx: State<'_, AppState>, name: String) -> Result<(), ClientworksError> {
+    api::swarm::disconnect_swarm(&ctx, name).await
+}
+
+#[tauri::command]
+pub async fn kill_swarm(ctx: State<'_, AppState>, name: String) -> Result<(), ClientworksError> {
+    api::swarm::kill_swarm(&ctx, name).await
+}
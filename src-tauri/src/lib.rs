@@ -1,60 +1,89 @@
-mod api;
-mod client;
+mod commands;
 
-use std::{
-    fs, sync::{Mutex, Arc}
-};
+use clientworks_core::{api, http, AppState, EventBroadcast};
+use log::error;
 use tauri::Manager;
 
-pub struct AppState {
-    pub com_channel: Mutex<client::hooks::Channel>,
-    pub api_context: Arc<Mutex<api::ApiContext>>,
-}
-
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub async fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_clipboard_manager::init())
         .setup(|app| {
             let path = app.path().app_data_dir().unwrap();
-            fs::create_dir_all(&path)
-                .expect(format!("Failed to create data directory at: {}",
-                                path.display()).as_str()
-                );
-            app.manage(AppState {
-                com_channel: Mutex::new(client::hooks::init(app.handle().clone())),
-                api_context: Arc::new(Mutex::new(api::load_from_dir(app.path().app_data_dir().unwrap())))
-            });
-            {
-                let state = app.state::<AppState>();
-                state.com_channel.lock().unwrap().init_chatlog(app.handle().clone());
+            let events = EventBroadcast::new();
+            let sink = (commands::TauriSink(app.handle().clone()), events.clone());
+            let state = AppState::load(path.clone(), sink);
+            tokio::spawn(api::refresh_server_statuses(state.clone(), api::STATUS_TTL));
+            tokio::spawn(api::auth::refresh_tokens(state.clone(), api::auth::REFRESH_INTERVAL));
+            let config = http::HttpConfig::from_file(&path)?;
+            if config.enabled {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = http::start(state, events, config.port).await {
+                        error!("Failed to start HTTP API: {e}");
+                    }
+                });
             }
+            app.manage(state);
 
             Ok(())
         })
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
-            api::get_servers,
-            api::add_server,
-            api::delete_server,
-            api::remove_client,
-            api::get_client,
-            api::get_client_by_user,
-            api::get_clients,
-            api::auth::auth_validity,
-            api::auth::recall_authentication,
-            api::auth::auth_offline,
-            api::auth::auth_ms_cache,
-            api::auth::auth_ms_init,
-            api::auth::auth_ms_finish,
-            api::controller::create_connection,
-            api::controller::connect_client,
-            api::controller::disconnect_client,
-            api::controller::send_chat,
-            api::controller::kill_client,
-            api::controller::kill_client_soft,
-            api::controller::get_instances,
-            api::controller::get_available_versions,
+            commands::get_servers,
+            commands::get_server_status,
+            commands::get_server_statuses,
+            commands::add_server,
+            commands::delete_server,
+            commands::remove_client,
+            commands::get_client,
+            commands::get_client_by_user,
+            commands::get_clients,
+            commands::auth::auth_validity,
+            commands::auth::auth_yggdrasil,
+            commands::auth::get_auth_cache_state,
+            commands::auth::unlock_auth_cache,
+            commands::auth::set_auth_passphrase,
+            commands::auth::recall_authentication,
+            commands::auth::auth_offline,
+            commands::auth::auth_ms_cache,
+            commands::auth::auth_ms_init,
+            commands::auth::auth_ms_finish,
+            commands::controller::create_connection,
+            commands::controller::connect_client,
+            commands::controller::disconnect_client,
+            commands::controller::send_chat,
+            commands::controller::send_movement,
+            commands::controller::get_instance_status,
+            commands::controller::get_tab_list,
+            commands::controller::set_reconnect_policy,
+            commands::controller::set_instance_script,
+            commands::controller::set_command_prefix,
+            commands::controller::get_scripts,
+            commands::controller::get_logs,
+            commands::controller::get_chat_since,
+            commands::controller::kill_client,
+            commands::controller::kill_client_soft,
+            commands::controller::get_instances,
+            commands::controller::get_available_versions,
+            commands::proxy::get_proxies,
+            commands::proxy::add_proxy,
+            commands::proxy::delete_proxy,
+            commands::proxy::set_client_proxy,
+            commands::proxy::set_instance_proxy,
+            commands::swarm::get_swarms,
+            commands::swarm::create_swarm,
+            commands::swarm::delete_swarm,
+            commands::swarm::set_swarm_proxy,
+            commands::swarm::launch_swarm,
+            commands::swarm::disconnect_swarm,
+            commands::swarm::kill_swarm,
+            commands::macros::get_macros,
+            commands::macros::save_macro,
+            commands::macros::delete_macro,
+            commands::macros::start_macro,
+            commands::macros::cancel_macro,
+            commands::macros::get_macro_runs,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -2,6 +2,7 @@ import { invoke }              from "@tauri-apps/api/core";
 import { listen, UnlistenFn }  from "@tauri-apps/api/event";
 import { useState, useEffect } from "react";
 import { MinecraftProfile}     from "./ClientTypes.tsx";
+import { describeError }       from "./Errors.ts";
 
 type Callback = {
     onClose: () => void;
@@ -30,9 +31,23 @@ async function cachedAuth(key: string): Promise<[string, MinecraftProfile]> {
     return await invoke("auth_ms_cache", { loginKey: key })
 }
 
+type YggdrasilServer = {
+    auth_url: string;
+    session_url: string;
+    services_url: string | null;
+}
+
+async function yggdrasilAuth(key: string, server: YggdrasilServer, password: string): Promise<[string, MinecraftProfile]> {
+    return await invoke("auth_yggdrasil", { loginKey: key, server: server, username: key, password: password })
+}
+
+type AuthType = "microsoft" | "yggdrasil" | "offline";
+
 function Auth({onClose, onFinish}: Callback) {
-    const [authType, setAuthType] = useState<"microsoft" | "offline">("microsoft");
+    const [authType, setAuthType] = useState<AuthType>("microsoft");
     const [loginKey, setLoginKey] = useState("");
+    const [password, setPassword] = useState("");
+    const [server, setServer] = useState<YggdrasilServer>({auth_url: "", session_url: "", services_url: null});
     const [isAuthenticating, setIsAuthenticating] = useState(false);
     const [credentials, setCredentials] = useState<{uri: string, code: string}>({uri: "", code: ""});
     const [label, setLabel] = useState({
@@ -60,7 +75,14 @@ function Auth({onClose, onFinish}: Callback) {
         if (!loginKey.trim()) {
             setLabel({
                 type: "error",
-                message: "Please enter " + (authType === "microsoft" ? "an email" : "a username")
+                message: "Please enter " + (authType === "offline" ? "a username" : "an email")
+            });
+            return;
+        }
+        if (authType === "yggdrasil" && (!server.auth_url.trim() || !server.session_url.trim())) {
+            setLabel({
+                type: "error",
+                message: "Please enter the authentication and session server URLs"
             });
             return;
         }
@@ -72,7 +94,7 @@ function Auth({onClose, onFinish}: Callback) {
                     .then(
                         result => completeAuth(result)
                     ).catch(e => {
-                        console.log("Cached auth failed: " + e);
+                        console.log("Cached auth failed: " + describeError(e));
                         let credentialsRequest: Promise<Credentials> = invoke("auth_ms_init", {loginKey: loginKey});
                         credentialsRequest.then(e => {
                             setCredentials({
@@ -84,6 +106,11 @@ function Auth({onClose, onFinish}: Callback) {
                                 throw (e)
                             });
                     });
+            } else if (authType === "yggdrasil") {
+                await yggdrasilAuth(loginKey, server, password)
+                    .then(
+                        result => completeAuth(result)
+                    ).catch(e => { throw(e) });
             } else {
                 await offlineAuth(loginKey)
                     .then(
@@ -93,7 +120,7 @@ function Auth({onClose, onFinish}: Callback) {
         } catch (e) {
             setLabel({
                 type: "error",
-                message: "Authentication failed: " + e
+                message: "Authentication failed: " + describeError(e)
             });
         } finally {
             setIsAuthenticating(false);
@@ -130,7 +157,7 @@ function Auth({onClose, onFinish}: Callback) {
         } catch (e) {
             setLabel({
                 type: "error",
-                message: "Authentication failed: " + e
+                message: "Authentication failed: " + describeError(e)
             });
         }
     };
@@ -171,17 +198,27 @@ function Auth({onClose, onFinish}: Callback) {
                             type="radio"
                             value="microsoft"
                             checked={authType === "microsoft"}
-                            onChange={(e) => setAuthType(e.target.value as "microsoft" | "offline")}
+                            onChange={(e) => setAuthType(e.target.value as AuthType)}
                             className="form-radio text-green-500 focus:ring-green-500"
                         />
                         <span className="ml-2 text-white">Microsoft Account</span>
                     </label>
+                    <label className="inline-flex items-center">
+                        <input
+                            type="radio"
+                            value="yggdrasil"
+                            checked={authType === "yggdrasil"}
+                            onChange={(e) => setAuthType(e.target.value as AuthType)}
+                            className="form-radio text-green-500 focus:ring-green-500"
+                        />
+                        <span className="ml-2 text-white">Yggdrasil</span>
+                    </label>
                     <label className="inline-flex items-center">
                         <input
                             type="radio"
                             value="offline"
                             checked={authType === "offline"}
-                            onChange={(e) => setAuthType(e.target.value as "microsoft" | "offline")}
+                            onChange={(e) => setAuthType(e.target.value as AuthType)}
                             className="form-radio text-green-500 focus:ring-green-500"
                         />
                         <span className="ml-2 text-white">Offline Account</span>
@@ -198,6 +235,49 @@ function Auth({onClose, onFinish}: Callback) {
                             className="w-full px-4 py-2 rounded bg-slate-700 text-white
                             focus:outline-none focus:ring-2 focus:ring-green-500"
                         />
+                    ) : authType === "yggdrasil" ? (
+                        <>
+                            <input
+                                type="url"
+                                value={server.auth_url}
+                                onChange={(e) => setServer({...server, auth_url: e.target.value})}
+                                placeholder="Authentication server, e.g. https://auth.example.net/authserver"
+                                className="w-full px-4 py-2 rounded bg-slate-700 text-white
+                                focus:outline-none focus:ring-2 focus:ring-green-500"
+                            />
+                            <input
+                                type="url"
+                                value={server.session_url}
+                                onChange={(e) => setServer({...server, session_url: e.target.value})}
+                                placeholder="Session server, e.g. https://auth.example.net/sessionserver"
+                                className="w-full px-4 py-2 rounded bg-slate-700 text-white
+                                focus:outline-none focus:ring-2 focus:ring-green-500"
+                            />
+                            <input
+                                type="url"
+                                value={server.services_url ?? ""}
+                                onChange={(e) => setServer({...server, services_url: e.target.value || null})}
+                                placeholder="Services (optional)"
+                                className="w-full px-4 py-2 rounded bg-slate-700 text-white
+                                focus:outline-none focus:ring-2 focus:ring-green-500"
+                            />
+                            <input
+                                type="text"
+                                value={loginKey}
+                                onChange={(e) => setLoginKey(e.target.value)}
+                                placeholder="Insert username or email"
+                                className="w-full px-4 py-2 rounded bg-slate-700 text-white
+                                focus:outline-none focus:ring-2 focus:ring-green-500"
+                            />
+                            <input
+                                type="password"
+                                value={password}
+                                onChange={(e) => setPassword(e.target.value)}
+                                placeholder="Insert password"
+                                className="w-full px-4 py-2 rounded bg-slate-700 text-white
+                                focus:outline-none focus:ring-2 focus:ring-green-500"
+                            />
+                        </>
                     ) : (
                         <input
                             type="text"
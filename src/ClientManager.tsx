@@ -7,16 +7,107 @@ import { ClientEntry,
 import {listen, UnlistenFn}   from "@tauri-apps/api/event";
 import Auth                   from './Auth';
 import { AnsiHtml }           from 'fancy-ansi/react'
+import { describeError }      from './Errors';
 
 // TODO refactor into actual modular types
 type ChatEventPayload = {
+    seq: number,
+    timestamp: number,
     message: string
 }
 
-type ChatEvent = {
+type DisconnectReason =
+    | { kind: "User" }
+    | { kind: "Killed" }
+    | { kind: "Kicked", message: string }
+    | { kind: "Lost" };
+
+type ConnectionState =
+    | { state: "Idle" }
+    | { state: "Resolving" }
+    | { state: "Connecting" }
+    | { state: "LoggingIn" }
+    | { state: "Playing" }
+    | { state: "Disconnecting" }
+    | { state: "Disconnected", reason: DisconnectReason }
+    | { state: "Failed", error: ConnectionError }
+    | { state: "Reconnecting", attempt: number, delay_secs: number };
+
+type ConnectionError = {
+    kind: "Network" | "Auth" | "VersionMismatch" | "KickedDuringLogin" | "Other",
+    message: string
+};
+
+type Telemetry = {
+    x: number,
+    y: number,
+    z: number,
+    yaw: number,
+    pitch: number,
+    health: number,
+    food: number,
+    saturation: number,
+    experience: { level: number, progress: number, total: number },
+    game_mode: string,
+    dimension: string
+}
+
+type InstanceStatus = {
+    state: ConnectionState,
+    telemetry: Telemetry | null
+}
+
+type InstanceEvent = {
     event: string,
     id?: number,
-    payload: { Chat: ChatEventPayload }
+    payload: {
+        Chat?: { lines: ChatEventPayload[] },
+        State?: { state: ConnectionState },
+        Telemetry?: { telemetry: Telemetry }
+    }
+}
+
+const isRunning = (state: ConnectionState) =>
+    ["Resolving", "Connecting", "LoggingIn", "Playing", "Reconnecting"].includes(state.state);
+
+const describeState = (state: ConnectionState): string => {
+    switch (state.state) {
+        case "LoggingIn":
+            return "Logging in";
+        case "Disconnected":
+            switch (state.reason.kind) {
+                case "User":
+                    return "Disconnected";
+                case "Killed":
+                    return "Killed";
+                case "Kicked":
+                    return `Kicked: ${state.reason.message}`;
+                case "Lost":
+                    return "Connection lost";
+            }
+            break;
+        case "Failed":
+            return `Failed: ${describeConnectionError(state.error)}`;
+        case "Reconnecting":
+            return `Reconnecting in ${state.delay_secs}s (attempt ${state.attempt})`;
+        default:
+            return state.state;
+    }
+}
+
+const describeConnectionError = (error: ConnectionError): string => {
+    switch (error.kind) {
+        case "Network":
+            return `Network error: ${error.message}`;
+        case "Auth":
+            return `Authentication error: ${error.message}`;
+        case "VersionMismatch":
+            return `Version mismatch: ${error.message}`;
+        case "KickedDuringLogin":
+            return `Kicked during login: ${error.message}`;
+        default:
+            return error.message;
+    }
 }
 
 type BackendClientConnection = {
@@ -29,6 +120,7 @@ type Connection = {
     id: string,
     server: string,
     version: string,
+    state: ConnectionState,
     connected: boolean
 }
 
@@ -43,8 +135,9 @@ export default function ClientManager() {
     const [connections, setConnections] = useState<Connection[]>([]);
     const [expandedConnection, setExpandedConnection] = useState<number>(-1);
 
-    const [chatHistory, setChatHistory] = useState<Record<string, string[]>>({});
+    const [chatHistory, setChatHistory] = useState<Record<string, ChatEventPayload[]>>({});
     const [chatMessage, setChatMessage] = useState<string>("");
+    const [telemetry, setTelemetry] = useState<Record<string, Telemetry | null>>({});
 
     const [versions, setVersions] = useState<string[]>([]);
     const [servers, setServers] = useState<string[]>([]);
@@ -56,6 +149,20 @@ export default function ClientManager() {
 
     const connectionListener = useRef<UnlistenFn | undefined>();
 
+    // merges live and backfilled chat lines, which may arrive in any order
+    const appendChat = (key: string, lines: ChatEventPayload[]) => {
+        setChatHistory((current) => {
+            const existing = current[key] || [];
+            const known = new Set(existing.map(line => line.seq));
+            const added = lines.filter(line => !known.has(line.seq));
+            if (added.length == 0) return current;
+            return {
+                ...current,
+                [key]: [...existing, ...added].sort((a, b) => a.seq - b.seq)
+            };
+        });
+    }
+
     const sendChatMessage = (connection: Connection, message: string) => {
         if (!allowed || !client) return;
         if (chatMessage.trim()) {
@@ -66,7 +173,7 @@ export default function ClientManager() {
                 })
                 .catch(e => {
                     console.log(e);
-                    setErrLabel(e);
+                    setErrLabel(describeError(e));
                 });
         }
     }
@@ -105,7 +212,7 @@ export default function ClientManager() {
     const pollStatus = async () => {
         if (!client) return;
 
-        type PollResponse = Record<string, [boolean, BackendClientConnection]>;
+        type PollResponse = Record<string, [ConnectionState, BackendClientConnection]>;
         const poll: Promise<PollResponse> =
             invoke("get_instances", {id: client.id});
         poll.then((response: PollResponse) => {
@@ -129,7 +236,8 @@ export default function ClientManager() {
                         id: connection.id,
                         server: connection.server.name,
                         version: connection.version,
-                        connected: state
+                        state: state,
+                        connected: isRunning(state)
                     } as Connection;
                 }
             ))
@@ -148,7 +256,7 @@ export default function ClientManager() {
                 })
                 .catch(e => {
                     console.error('Authentication error:', e);
-                    setAllowed({value: false, validating: false, error: e});
+                    setAllowed({value: false, validating: false, error: describeError(e)});
                     setLoading(false);
                 })
         }
@@ -215,6 +323,7 @@ export default function ClientManager() {
                                             onChange={(e) => setSelectedVersion(e.target.value)}
                                         >
                                             <option value="">Select version...</option>
+                                            <option value="auto">Detect automatically</option>
                                             {versions.map((version) => (
                                                 <option key={version} value={version}>{version}</option>
                                             ))}
@@ -271,6 +380,7 @@ export default function ClientManager() {
                                                         pollStatus();
                                                     }).catch((error) => {
                                                         console.error('Failed to create connection:', error);
+                                                        setErrLabel(describeError(error));
                                                     });
                                                 }
                                             }}
@@ -302,17 +412,42 @@ export default function ClientManager() {
 
                                     if (newConnection != -1) {
                                         setErrLabel('');
-                                        listen(connection.id, (e: ChatEvent) => {
-                                            setChatHistory(
-                                                (current) => ({
-                                                    ...current,
-                                                    [connection.id]: [
-                                                        ...(current[connection.id] || []),
-                                                        e.payload.Chat.message
-                                                    ]
-                                                })
-                                            )
-                                        }).then(unlisten => connectionListener.current = unlisten)
+                                        listen(connection.id, (e: InstanceEvent) => {
+                                            const chat = e.payload.Chat;
+                                            if (chat) {
+                                                appendChat(connection.id, chat.lines);
+                                            }
+                                            const sample = e.payload.Telemetry;
+                                            if (sample) {
+                                                setTelemetry((current) => ({...current, [connection.id]: sample.telemetry}));
+                                            }
+                                            const update = e.payload.State;
+                                            if (update && !isRunning(update.state)) {
+                                                setTelemetry((current) => ({...current, [connection.id]: null}));
+                                            }
+                                            if (update) {
+                                                setConnections((current) => current.map(conn =>
+                                                    conn.id === connection.id
+                                                        ? {...conn, state: update.state, connected: isRunning(update.state)}
+                                                        : conn
+                                                ));
+                                            }
+                                        }).then(unlisten => {
+                                            connectionListener.current = unlisten;
+                                            // backfill what was received while the instance was closed
+                                            const lines = chatHistory[connection.id] || [];
+                                            const seq = lines.length ? lines[lines.length - 1].seq : 0;
+                                            invoke<ChatEventPayload[]>("get_chat_since", {
+                                                id: client?.id, key: connection.id, seq
+                                            })
+                                                .then(missed => appendChat(connection.id, missed))
+                                                .catch(e => setErrLabel(describeError(e)));
+                                            invoke<InstanceStatus>("get_instance_status", {
+                                                id: client?.id, key: connection.id
+                                            })
+                                                .then(status => setTelemetry((current) => ({...current, [connection.id]: status.telemetry})))
+                                                .catch(e => setErrLabel(describeError(e)));
+                                        })
                                     }
                                 }}
                             >
@@ -321,9 +456,11 @@ export default function ClientManager() {
                                     <span className="text-gray-400 text-sm"> - {connection.version}</span>
                                     <span
                                         className={`mx-2 inline-block w-16 h-2 rounded-full ${
-                                            connection.connected ? 'bg-green-400' : 'bg-red-400'
+                                            connection.state.state === 'Playing' ? 'bg-green-400'
+                                                : connection.connected ? 'bg-yellow-400' : 'bg-red-400'
                                             }`}>
                                     </span>
+                                    <span className="text-sm text-gray-400 mr-2">{describeState(connection.state)}</span>
                                     <span className="font-normal text-sm text-gray-500">id: {connection.id}</span>
                                 </div>
                                 <i className={`fa fa-chevron-${expandedConnection == index ? 'up' : 'down'}`}/>
@@ -341,7 +478,7 @@ export default function ClientManager() {
                                                     await invoke("connect_client", {id: client.id, key: connection.id});
                                                     setErrLabel(null)
                                                 } catch (e) {
-                                                    setErrLabel(e as string)
+                                                    setErrLabel(describeError(e))
                                                 }
                                             }}
                                         >
@@ -360,7 +497,7 @@ export default function ClientManager() {
                                                     });
                                                     setErrLabel(null);
                                                 } catch (e) {
-                                                    setErrLabel(e as string);
+                                                    setErrLabel(describeError(e));
                                                 }
                                             }}
                                         >
@@ -380,7 +517,7 @@ Does not guarantee instant client disconnection, will likely be recognised as a
                                                         });
                                                         setErrLabel(null);
                                                     } catch (e) {
-                                                        setErrLabel(e as string);
+                                                        setErrLabel(describeError(e));
                                                     }
                                                 }}
                                             >
@@ -393,14 +530,34 @@ Does not guarantee instant client disconnection, will likely be recognised as a
                                             </div>
                                         }
                                     </div>
+                                    {telemetry[connection.id] && (() => {
+                                        const t = telemetry[connection.id]!;
+                                        return (
+                                            <div className="grid grid-cols-4 gap-2 mb-4 text-sm text-gray-300">
+                                                <span title={`yaw ${t.yaw.toFixed(0)}, pitch ${t.pitch.toFixed(0)}`}>
+                                                    <i className="fa fa-map-marker mr-1"/>
+                                                    {t.x.toFixed(1)} {t.y.toFixed(1)} {t.z.toFixed(1)}
+                                                </span>
+                                                <span><i className="fa fa-globe mr-1"/>{t.dimension} ({t.game_mode})</span>
+                                                <span>
+                                                    <i className="fa fa-heart mr-1 text-red-400"/>{t.health.toFixed(1)}
+                                                    <i className="fa fa-cutlery ml-3 mr-1 text-amber-400"/>{t.food}
+                                                    <span className="text-gray-500"> ({t.saturation.toFixed(1)})</span>
+                                                </span>
+                                                <span>
+                                                    <i className="fa fa-star mr-1 text-green-400"/>Level {t.experience.level}
+                                                    <span className="text-gray-500"> ({Math.round(t.experience.progress * 100)}%)</span>
+                                                </span>
+                                            </div>
+                                        );
+                                    })()}
                                     <div ref={chatContainerRef}
                                         className="bg-slate-800 rounded-lg p-4 h-96 overflow-y-auto mb-4">
-                                        {(chatHistory[connection.id] || []).map((message, idx) => (
-                                            <div>
+                                        {(chatHistory[connection.id] || []).map((line) => (
+                                            <div key={line.seq}>
                                                 <AnsiHtml
-                                                    key={idx}
                                                     className="text-sm font-normal"
-                                                    text={message}
+                                                    text={line.message}
                                                 />
                                             </div>
                                         ))}
@@ -443,8 +600,13 @@ Does not guarantee instant client disconnection, will likely be recognised as a
                             <p>This client has failed to authenticate.</p>
                             <p className="mt-2 text-sm text-red-300">
                                 <span>Error details: </span>
-                                <div dangerouslySetInnerHTML={{__html: allowed.error ? allowed.error : ''}}></div>
+                                {allowed.error && <span>{allowed.error}</span>}
                                 {!allowed.error && <span>Failed to authenticate client ${id}</span>}
+                                <br/><br/>
+                                <a className="text-red-500 underline"
+                                   href="https://github.com/errphoenix/clientworks/issues"
+                                   target="_blank"
+                                   rel="noopener noreferrer">Report a bug</a> if you believe this is an error.
                             </p>
                             <div className="flex justify-center">
                                 <button
@@ -2,6 +2,9 @@ export interface ClientEntry {
     id: string;
     username: string;
     auth: boolean;
+    auth_type: "Offline" | "Microsoft" | "Yggdrasil";
+    /** Whether the account is in the encrypted authentication cache, which is still locked */
+    locked: boolean;
     uuid?: string;
     instance_count: number;
 }
@@ -24,6 +27,8 @@ export class MinecraftProfile {
             id: id,
             username: this.username,
             auth: true,
+            auth_type: "Microsoft",
+            locked: false,
             uuid: this.uuid,
             instance_count: 0,
         } as ClientEntry;
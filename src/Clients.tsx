@@ -1,7 +1,8 @@
 import { ClientEntry }         from "./ClientTypes.tsx";
+import { describeError }       from "./Errors.ts";
 import { useNavigate }         from "react-router";
 import { invoke }              from "@tauri-apps/api/core";
-import { useEffect, useState } from "react";
+import React, { useEffect, useState } from "react";
 import Auth                    from "./Auth.tsx";
 
 export function ClientList({clients}: { clients: ClientEntry[] }) {
@@ -27,7 +28,9 @@ export function ClientList({clients}: { clients: ClientEntry[] }) {
                             <h3 className="font-semibold">{client.username}</h3>
                             <div className="flex-col space-x-2 text-sm">
                                 <p className="text-gray-400">
-                                    {client.auth ? 'Microsoft Account' : 'Offline Account'}
+                                    {client.auth
+                                        ? `${client.auth_type} Account` + (client.locked ? ' (locked)' : '')
+                                        : 'Offline Account'}
                                 </p>
                                 <p className="text-blue-400">
                                     {client.instance_count + ' instance' + (client.instance_count == 1 ? '' : 's')}
@@ -45,15 +48,53 @@ async function getClients(): Promise<ClientEntry[]> {
     return await invoke('get_clients')
 }
 
+function UnlockForm({onUnlock}: { onUnlock: () => void }) {
+    const [passphrase, setPassphrase] = useState('');
+    const [error, setError] = useState<string | null>(null);
+
+    const handleSubmit = (e: React.FormEvent) => {
+        e.preventDefault();
+        invoke('unlock_auth_cache', {passphrase: passphrase})
+            .then(() => {
+                setError(null);
+                onUnlock();
+            })
+            .catch(e => setError(describeError(e)));
+    };
+
+    return (
+        <form onSubmit={handleSubmit} className="flex items-center space-x-2 bg-slate-800 p-4 mx-4 rounded-lg">
+            <p className="flex-1 text-gray-400">
+                Microsoft accounts are locked, enter the passphrase of the authentication cache.
+                {error && <span className="block text-red-400">{error}</span>}
+            </p>
+            <input
+                type="password"
+                placeholder="Passphrase"
+                className="px-4 py-2 rounded bg-slate-700 text-white focus:outline-none focus:ring-2 focus:ring-fuchsia-500"
+                onChange={(e) => setPassphrase(e.target.value)}
+            />
+            <button
+                type="submit"
+                className="px-4 py-2 text-white bg-fuchsia-600 rounded hover:bg-fuchsia-800 duration-400"
+            >
+                Unlock
+            </button>
+        </form>
+    );
+}
+
 export default function Clients() {
     const [showAuth, setShowAuth] = useState(false);
     const [clients, setClients] = useState<ClientEntry[]>([]);
 
-    useEffect(() => {
+    const loadClients = () => {
         getClients()
             .then(e => setClients(e))
             .catch(e => console.error(e))
-    }, []);
+    };
+
+    useEffect(loadClients, []);
 
     return (
         <main className="p-4">
@@ -65,6 +106,7 @@ export default function Clients() {
                     <i className="fa fa-plus"/>
                 </button>
             </div>
+            {clients.some(client => client.locked) && <UnlockForm onUnlock={loadClients}/>}
             {clients && <ClientList clients={clients}/>}
             {showAuth && (
                 <Auth onClose={() => setShowAuth(false)}
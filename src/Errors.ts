@@ -0,0 +1,112 @@
+// Mirrors `ClientworksError` from the backend, see `src-tauri/core/src/api/error.rs`
+export type ClientworksError =
+    | { code: "InvalidId", id: string }
+    | { code: "ClientNotFound", id: string }
+    | { code: "ClientExists", username: string }
+    | { code: "ServerNotFound", name: string }
+    | { code: "ServerExists", name: string }
+    | { code: "ServerUnreachable", name: string, reason: string }
+    | { code: "InvalidVersion", version: string }
+    | { code: "UnsupportedVersion", name: string, version: string, protocol: number }
+    | { code: "SwarmNotFound", name: string }
+    | { code: "SwarmExists", name: string }
+    | { code: "MacroNotFound", name: string }
+    | { code: "InvalidMacro", name: string, reason: string }
+    | { code: "MacroRunNotFound", run: string }
+    | { code: "ScriptNotFound", name: string }
+    | { code: "ProxyNotFound", name: string }
+    | { code: "ProxyExists", name: string }
+    | { code: "InvalidProxy", proxy: string, reason: string }
+    | { code: "ProxyPoolEmpty" }
+    | { code: "AuthCacheLocked" }
+    | { code: "InvalidPassphrase", reason: string }
+    | { code: "InvalidCommandPrefix", prefix: string }
+    | { code: "ControllerMissing", id: string }
+    | { code: "InstanceNotFound", id: string, key: string }
+    | { code: "InstanceOffline", key: string }
+    | { code: "InstanceTimeout", key: string }
+    | { code: "AuthKeyMissing", uuid: string }
+    | { code: "AuthExpired", login_key: string, reason?: string }
+    | { code: "AuthFailed", reason: string }
+    | { code: "NoOngoingAuth", login_key: string }
+    | { code: "ProfileMissing" }
+    | { code: "Io", reason: string };
+
+export function isClientworksError(e: unknown): e is ClientworksError {
+    return typeof e === "object" && e !== null && "code" in e;
+}
+
+/**
+ * Returns a user-friendly description of an error returned by a command.
+ */
+export function describeError(e: unknown): string {
+    if (!isClientworksError(e)) {
+        return String(e);
+    }
+    switch (e.code) {
+        case "InvalidId":
+            return `Invalid ID: ${e.id}`;
+        case "ClientNotFound":
+            return `No client registered with ID: ${e.id}`;
+        case "ClientExists":
+            return `Account ${e.username} is already registered.`;
+        case "ServerNotFound":
+            return `Server '${e.name}' not found`;
+        case "ServerExists":
+            return `Server ${e.name} already exists`;
+        case "ServerUnreachable":
+            return `Could not reach server ${e.name}: ${e.reason}`;
+        case "InvalidVersion":
+            return `Unsupported version: ${e.version}`;
+        case "UnsupportedVersion":
+            return `Server ${e.name} runs ${e.version} (protocol ${e.protocol}), which is not supported.`;
+        case "SwarmNotFound":
+            return `Swarm '${e.name}' not found`;
+        case "SwarmExists":
+            return `Swarm ${e.name} already exists`;
+        case "MacroNotFound":
+            return `Macro '${e.name}' not found`;
+        case "InvalidMacro":
+            return `Invalid macro ${e.name}: ${e.reason}`;
+        case "MacroRunNotFound":
+            return "This macro run has already ended.";
+        case "ScriptNotFound":
+            return `Script '${e.name}' not found in the scripts folder`;
+        case "ProxyNotFound":
+            return `Proxy '${e.name}' not found`;
+        case "ProxyExists":
+            return `Proxy ${e.name} already exists`;
+        case "InvalidProxy":
+            return `Invalid proxy '${e.proxy}': ${e.reason}`;
+        case "ProxyPoolEmpty":
+            return "No proxy is saved to draw from the proxy pool.";
+        case "AuthCacheLocked":
+            return "The authentication cache is locked, unlock it with its passphrase first.";
+        case "InvalidPassphrase":
+            return `Invalid passphrase: ${e.reason}`;
+        case "InvalidCommandPrefix":
+            return `Invalid command prefix '${e.prefix}': it can't be empty, contain spaces or start with '/'.`;
+        case "ControllerMissing":
+            return `Client ${e.id} is not authenticated.`;
+        case "InstanceNotFound":
+            return `No client instance found from key: ${e.key}`;
+        case "InstanceOffline":
+            return "Cannot do this while the instance is offline.";
+        case "InstanceTimeout":
+            return "The instance took too long to shut down.";
+        case "AuthKeyMissing":
+            return `No authentication key found in cache for client with ID ${e.uuid}. Please check your account cache in auth_cache.json if allowed to.`;
+        case "AuthExpired":
+            return e.reason
+                ? `Failed to refresh authentication token, re-authentication is required: ${e.reason}`
+                : "Account not found in cache or cached token(s) have expired.";
+        case "AuthFailed":
+            return e.reason;
+        case "NoOngoingAuth":
+            return `No ongoing authentication found for ${e.login_key}`;
+        case "ProfileMissing":
+            return "No profile found from account.";
+        case "Io":
+            return `Failed to access data files: ${e.reason}`;
+    }
+}
@@ -1,18 +1,57 @@
 import {invoke}                     from "@tauri-apps/api/core";
 import React, {useEffect, useState} from "react";
 
+type ServerStatus = {
+    motd: string;
+    motd_ansi: string;
+    version: string;
+    protocol: number;
+    players: { online: number, max: number, sample: { name: string, id: string }[] };
+    favicon?: string;
+    latency: number;
+}
+
+type CachedStatus = {
+    server: string;
+    updated: number;
+    status?: ServerStatus;
+    error?: string;
+}
+
 type ServerEntry = {
     name: string;
     ip: string;
     port: number;
     connections: number;
+    status?: CachedStatus;
 }
 
-function get_server_ico(ip?: string): string {
-    if (!ip) {
+function get_server_ico(server: ServerEntry): string {
+    if (server.status?.status?.favicon) {
+        return server.status.status.favicon;
+    }
+    if (!server.ip) {
         return "https://mc-heads.net/helm/none/256";
     }
-    return `https://eu.mc-api.net/v3/server/favicon/${ip}`;
+    return `https://eu.mc-api.net/v3/server/favicon/${server.ip}`;
+}
+
+function ServerStatusLine({status}: { status?: CachedStatus }) {
+    if (!status) {
+        return <p className="text-gray-500">Querying status...</p>;
+    }
+    if (!status.status) {
+        return <p className="text-red-400" title={status.error}>Offline</p>;
+    }
+    const {players, latency, version, motd} = status.status;
+    return (
+        <>
+            <p className="text-gray-300 truncate" title={motd}>{motd.split("\n")[0]}</p>
+            <p className="text-green-400" title={players.sample.map(player => player.name).join(", ")}>
+                {players.online}/{players.max} online · {latency}ms · {version}
+            </p>
+        </>
+    );
 }
 
 function ServerList({servers, onRemove}: { servers: ServerEntry[], onRemove: (name: string) => void }) {
@@ -29,7 +68,7 @@ function ServerList({servers, onRemove}: { servers: ServerEntry[], onRemove: (na
                          onClick={() => console.log(server.name)}>
                         <div className="min-w-16 min-h-16 max-w-16 max-h-16 bg-slate-700 rounded-lg
                                 flex items-center justify-center">
-                            <img src={get_server_ico(server.ip)} alt="server icon"/>
+                            <img src={get_server_ico(server)} alt="server icon"/>
                         </div>
                         <div className="flex-1">
                             <h3 className="font-semibold">{server.name}</h3>
@@ -37,6 +76,7 @@ function ServerList({servers, onRemove}: { servers: ServerEntry[], onRemove: (na
                                 <p className="text-gray-400">
                                     {server.ip} {server.port}
                                 </p>
+                                <ServerStatusLine status={server.status}/>
                                 <p className="text-blue-400">
                                     {server.connections ? server.connections : 0}
                                     {server.connections == 1 ? " client" : " clients"} connected
@@ -65,6 +105,10 @@ async function getServers(): Promise<ServerEntry[]> {
     return await invoke('get_servers')
 }
 
+async function getServerStatuses(): Promise<CachedStatus[]> {
+    return await invoke('get_server_statuses', { refresh: false })
+}
+
 export default function Servers() {
     const [showDialog, setShowDialog] = useState(false);
     const [serverInput, setServerInput] = useState({name: "", ip: "", port: 25565});
@@ -73,6 +117,11 @@ export default function Servers() {
     useEffect(() => {
         getServers()
             .then(e => setServers(e))
+            .then(() => getServerStatuses())
+            .then(statuses => setServers(servers => servers.map(server => ({
+                ...server,
+                status: statuses.find(status => status.server === server.name) ?? server.status
+            }))))
             .catch(e => console.log(e))
     }, []);
 